@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MemberInfo, Zone};
+
+/// rules an assignment must follow, members and zones by name,
+/// rules of members not in the roster are ignored, so is a pair with one of them
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+  /// member to the zone it must be in
+  #[serde(default)]
+  pub pinned: BTreeMap<String, String>,
+  /// members that are not assigned to any zone
+  #[serde(default)]
+  pub excluded: BTreeSet<String>,
+  /// members that must not be in the same zone
+  #[serde(default)]
+  pub forbidden_pairs: BTreeSet<(String, String)>,
+  /// members that must be in the same zone
+  #[serde(default)]
+  pub required_pairs: BTreeSet<(String, String)>,
+}
+
+/// a pair in the same order no matter the order given
+fn pair(a: impl Into<String>, b: impl Into<String>) -> (String, String) {
+  let (a, b) = (a.into(), b.into());
+  if a <= b {
+    (a, b)
+  } else {
+    (b, a)
+  }
+}
+
+impl Constraints {
+  pub fn is_empty(&self) -> bool {
+    self.pinned.is_empty()
+      && self.excluded.is_empty()
+      && self.forbidden_pairs.is_empty()
+      && self.required_pairs.is_empty()
+  }
+
+  pub fn pin(&mut self, member: impl Into<String>, zone: impl Into<String>) {
+    self.pinned.insert(member.into(), zone.into());
+  }
+
+  pub fn exclude(&mut self, member: impl Into<String>) {
+    self.excluded.insert(member.into());
+  }
+
+  pub fn forbid_pair(&mut self, a: impl Into<String>, b: impl Into<String>) {
+    let pair = pair(a, b);
+    self.required_pairs.remove(&pair);
+    self.forbidden_pairs.insert(pair);
+  }
+
+  pub fn require_pair(&mut self, a: impl Into<String>, b: impl Into<String>) {
+    let pair = pair(a, b);
+    self.forbidden_pairs.remove(&pair);
+    self.required_pairs.insert(pair);
+  }
+
+  pub fn remove_pair(&mut self, a: impl Into<String>, b: impl Into<String>) {
+    let pair = pair(a, b);
+    self.forbidden_pairs.remove(&pair);
+    self.required_pairs.remove(&pair);
+  }
+
+  /// every rule of the member
+  pub fn remove_member(&mut self, member: &str) {
+    self.pinned.remove(member);
+    self.excluded.remove(member);
+    self
+      .forbidden_pairs
+      .retain(|(a, b)| a != member && b != member);
+    self
+      .required_pairs
+      .retain(|(a, b)| a != member && b != member);
+  }
+
+  /// pins to the zone
+  pub fn remove_zone(&mut self, zone: &str) {
+    self.pinned.retain(|_, it| it != zone);
+  }
+
+  /// without the pairs of a member not in the roster
+  pub fn for_roster(&self, roster: &[MemberInfo]) -> Self {
+    let in_roster = |(a, b): &(String, String)| {
+      roster.iter().any(|it| &it.name == a) && roster.iter().any(|it| &it.name == b)
+    };
+
+    Self {
+      forbidden_pairs: self
+        .forbidden_pairs
+        .iter()
+        .filter(|it| in_roster(it))
+        .cloned()
+        .collect(),
+      required_pairs: self
+        .required_pairs
+        .iter()
+        .filter(|it| in_roster(it))
+        .cloned()
+        .collect(),
+      ..self.clone()
+    }
+  }
+
+  pub fn is_excluded(&self, member: &MemberInfo) -> bool {
+    self.excluded.contains(&member.name)
+  }
+
+  /// if a member that must be assigned is excluded
+  pub fn is_conflicting(&self, roster: &[MemberInfo]) -> bool {
+    roster
+      .iter()
+      .any(|it| self.is_excluded(it) && !self.allows_unassigned(std::slice::from_ref(it)))
+  }
+
+  /// if the zone can be filled with the team, from the members not assigned to the zones before
+  pub fn allows(&self, zone: &Zone, team: &[MemberInfo], unassigned: &[MemberInfo]) -> bool {
+    let pinned_to = |member: &MemberInfo| self.pinned.get(&member.name);
+    let in_team = |name: &String| team.iter().any(|it| &it.name == name);
+
+    // members pinned here are only left for this zone
+    let pinned_here = unassigned
+      .iter()
+      .filter(|it| pinned_to(it) == Some(&zone.name))
+      .all(|it| team.contains(it));
+    let pinned_elsewhere = team
+      .iter()
+      .any(|it| pinned_to(it).is_some_and(|pinned| pinned != &zone.name));
+    let forbidden = self
+      .forbidden_pairs
+      .iter()
+      .any(|(a, b)| in_team(a) && in_team(b));
+    let split = self
+      .required_pairs
+      .iter()
+      .any(|(a, b)| in_team(a) != in_team(b));
+
+    pinned_here && !pinned_elsewhere && !forbidden && !split
+  }
+
+  /// if the members can be left unassigned, as they are after the last zone
+  pub fn allows_unassigned(&self, members: &[MemberInfo]) -> bool {
+    members.iter().all(|it| {
+      !self.pinned.contains_key(&it.name)
+        && !self
+          .required_pairs
+          .iter()
+          .any(|(a, b)| a == &it.name || b == &it.name)
+    })
+  }
+}
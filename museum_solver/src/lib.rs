@@ -2,7 +2,13 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, RangeInclusive, Sub, SubAssign};
+
+pub use constraint::Constraints;
+pub use objective::{Objective, Score};
+
+mod constraint;
+mod objective;
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct MemberInfo {
@@ -100,12 +106,25 @@ pub struct Zone {
   pub require: Attribute,
   #[serde(default = "default_base_scaler")]
   pub base_scaler: u64,
+  /// least assistants of the zone, it's only left empty if fewer members are left for it
+  #[serde(default = "default_min_assistants")]
+  pub min_assistants: usize,
+  #[serde(default = "default_max_assistants")]
+  pub max_assistants: usize,
 }
 
 fn default_base_scaler() -> u64 {
   100
 }
 
+fn default_min_assistants() -> usize {
+  1
+}
+
+fn default_max_assistants() -> usize {
+  3
+}
+
 impl Zone {
   pub fn new(
     name: impl Into<String>,
@@ -120,22 +139,34 @@ impl Zone {
       sub_level,
       require,
       base_scaler,
+      min_assistants: default_min_assistants(),
+      max_assistants: default_max_assistants(),
+    }
+  }
+
+  pub fn with_assistants(mut self, min: usize, max: usize) -> Self {
+    self.min_assistants = min;
+    self.max_assistants = max;
+    self
+  }
+
+  /// number of members the zone can be filled with from the members available,
+  /// only zero for left empty if fewer than [Zone::min_assistants] are available
+  pub fn team_sizes(&self, available: usize) -> RangeInclusive<usize> {
+    if available < self.min_assistants {
+      0..=0
+    } else {
+      self.min_assistants..=self.max_assistants.min(available)
     }
   }
 
   pub fn calc(&self, member: &[MemberInfo]) -> CalcResult {
-    assert!(!member.is_empty() && member.len() <= 3);
+    assert!(member.len() <= self.max_assistants);
 
-    let mut req = self.require.clone()
-      - (self.base.clone() + self.sub_level.clone().mul_by(10))
-        .mul_byf(self.base_scaler as f64 / 100_f64);
+    let req = self.shortfall(member);
     let mut require: u64 = 0;
     let mut overflow: u64 = 0;
 
-    for info in member {
-      req -= info.attr.clone();
-    }
-
     if req.time >= 0 {
       require += req.time as u64;
     } else {
@@ -166,6 +197,11 @@ impl Zone {
       + (self.base.clone() + self.sub_level.clone().mul_by(10))
         .mul_byf(self.base_scaler as f64 / 100_f64)
   }
+
+  /// required minus what the zone has with the members, negative for overflow
+  pub fn shortfall(&self, member: &[MemberInfo]) -> Attribute {
+    self.require.clone() - self.calc_detail(member)
+  }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -196,7 +232,7 @@ impl CalcResult {
   }
 }
 
-type SolveResult = (CalcResult, HashMap<Zone, Vec<MemberInfo>>);
+type SolveResult<S = CalcResult> = (S, HashMap<Zone, Vec<MemberInfo>>);
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct SolveState {
@@ -205,16 +241,21 @@ pub struct SolveState {
 }
 
 impl SolveState {
-  pub fn can_next(&self) -> bool {
-    self.members.len() >= 6 && self.zones.len() >= 2
-  }
-
-  pub fn current_zone(&self) -> &Zone {
-    &self.zones[0]
+  pub fn current_zone(&self) -> Option<&Zone> {
+    self.zones.first()
   }
 
-  pub fn member_combinations(&self) -> Vec<Vec<MemberInfo>> {
-    self.members.iter().cloned().combinations(3).collect_vec()
+  /// every team the current zone can be filled with, see [Zone::team_sizes]
+  pub fn member_combinations(&self, constraints: &Constraints) -> Vec<Vec<MemberInfo>> {
+    let Some(zone) = self.current_zone() else {
+      return vec![];
+    };
+
+    zone
+      .team_sizes(self.members.len())
+      .flat_map(|size| self.members.iter().cloned().combinations(size))
+      .filter(|it| constraints.allows(zone, it, &self.members))
+      .collect_vec()
   }
 
   pub fn next(&self, members: &[MemberInfo]) -> Self {
@@ -231,60 +272,116 @@ impl SolveState {
   }
 }
 
+/// the assignment with the least [CalcResult] over every zone,
+/// each zone in order is filled with [Zone::min_assistants] to [Zone::max_assistants] members,
+/// or left empty if fewer members than [Zone::min_assistants] are left after the zones before,
+/// None if there is no zone or a zone can't be filled
 pub fn solve(members: Vec<MemberInfo>, zones: Vec<Zone>) -> Option<SolveResult> {
-  if members.len() < 3 || zones.is_empty() {
-    return None;
+  let (score, assignment) = solve_with(
+    members,
+    zones,
+    &Objective::Lexicographic,
+    &Constraints::default(),
+  )?;
+
+  Some((CalcResult::new(score.primary, score.secondary), assignment))
+}
+
+/// the assignment with the least [Score] of the objective, see [solve]
+pub fn solve_with(
+  members: Vec<MemberInfo>,
+  zones: Vec<Zone>,
+  objective: &Objective,
+  constraints: &Constraints,
+) -> Option<SolveResult<Score>> {
+  solve_top_k(members, zones, objective, constraints, 1)
+    .into_iter()
+    .next()
+}
+
+/// the k assignments with the least [Score] of the objective from the least, each different,
+/// fewer if there aren't as many, empty if there is no zone, a zone can't be filled
+/// or the constraints can't be met
+pub fn solve_top_k(
+  members: Vec<MemberInfo>,
+  zones: Vec<Zone>,
+  objective: &Objective,
+  constraints: &Constraints,
+  k: usize,
+) -> Vec<SolveResult<Score>> {
+  let constraints = &constraints.for_roster(&members);
+  if k == 0
+    || zones.is_empty()
+    || zones
+      .iter()
+      .any(|it| it.max_assistants == 0 || it.min_assistants > it.max_assistants)
+    || constraints.is_conflicting(&members)
+  {
+    return vec![];
   }
 
+  let members = members
+    .into_iter()
+    .filter(|it| !constraints.is_excluded(it))
+    .collect_vec();
   let mut cache = HashMap::new();
 
-  Some(solve_inner(
+  solve_inner(
     SolveState { members, zones },
-    CalcResult::new(0, 0),
+    objective,
+    constraints,
+    k,
     &mut cache,
-  ))
+  )
 }
 
+/// the k least scores of the zones in the state from the least, and the members of each,
+/// depends on the state only so it's cached without the cost of the zones before
 fn solve_inner(
   state: SolveState,
-  prev_total: CalcResult,
-  cache: &mut HashMap<SolveState, (SolveResult, CalcResult)>,
-) -> SolveResult {
-  if let Some((solve_result, result)) = cache.get(&state) {
-    if *result <= prev_total {
-      return solve_result.clone();
-    }
+  objective: &Objective,
+  constraints: &Constraints,
+  k: usize,
+  cache: &mut HashMap<SolveState, Vec<SolveResult<Score>>>,
+) -> Vec<SolveResult<Score>> {
+  if let Some(solve_results) = cache.get(&state) {
+    return solve_results.clone();
   }
 
-  let combinations = state.member_combinations();
-
-  let mut min_result = (
-    CalcResult::new(u64::MAX, u64::MAX),
-    HashMap::<Zone, Vec<MemberInfo>>::new(),
-  );
-
-  for members in combinations {
-    let mut result = state.current_zone().calc(&members) + prev_total;
-    if result >= min_result.0 {
-      continue;
+  let Some(zone) = state.current_zone() else {
+    if !constraints.allows_unassigned(&state.members) {
+      return vec![];
     }
-
-    let mut zones = HashMap::with_capacity(1);
-
-    if state.can_next() {
-      let next = solve_inner(state.next(&members), result, cache);
-
-      result = next.0;
-      zones = next.1;
+    return vec![(Score::default(), HashMap::new())];
+  };
+
+  let mut min_results: Vec<SolveResult<Score>> = Vec::new();
+  let is_worse = |min_results: &Vec<SolveResult<Score>>, score: Score| {
+    min_results.len() == k && score >= min_results[k - 1].0
+  };
+
+  for members in state.member_combinations(constraints) {
+    let score = objective.score(zone, &members);
+    // the zones after never lower the score
+    if is_worse(&min_results, score) {
+      continue;
     }
 
-    if min_result.0 > result {
-      zones.insert(state.current_zone().clone(), members);
-      min_result = (result, zones)
+    for (next, mut zones) in solve_inner(state.next(&members), objective, constraints, k, cache) {
+      let result = objective.combine(score, next);
+      // the rest of the next are no better
+      if is_worse(&min_results, result) {
+        break;
+      }
+
+      zones.insert(zone.clone(), members.clone());
+      let idx = min_results.partition_point(|it| it.0 <= result);
+      min_results.insert(idx, (result, zones));
+      min_results.truncate(k);
     }
   }
 
-  cache.insert(state, (min_result.clone(), min_result.0));
+  cache.insert(state, min_results.clone());
 
-  min_result
+  min_results
 }
@@ -6,37 +6,65 @@ use std::str::FromStr;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use museum_solver::{solve, Attribute, MemberInfo, Zone};
+use museum_solver::{solve_top_k, Attribute, CalcResult, Constraints, MemberInfo, Objective, Zone};
+
+/// most assignments listed by solve
+const MAX_SOLUTIONS: usize = 100;
 
 #[derive(Serialize, Deserialize)]
 struct State {
   members: Vec<MemberInfo>,
   zones: Vec<Zone>,
+  #[serde(default)]
+  objective: Objective,
+  #[serde(default)]
+  constraints: Constraints,
 }
 
 impl State {
-  pub fn solve_print(&self) {
+  /// the count best assignments from the best
+  pub fn solve_print(&self, count: usize) {
     let members = self.members.iter().cloned().collect_vec();
     let zones = self.zones.iter().cloned().collect_vec();
 
-    let solve_result = solve(members, zones);
+    let solve_results = solve_top_k(members, zones, &self.objective, &self.constraints, count);
+
+    if solve_results.is_empty() {
+      println!("计算失败");
+    }
+
+    for (idx, (score, assignment)) in solve_results.into_iter().enumerate() {
+      let total = assignment
+        .iter()
+        .map(|(zone, members)| zone.calc(members))
+        .fold(CalcResult::new(0, 0), |acc, it| acc + it);
 
-    if let Some(solve_result) = solve_result {
-      print!("结果:");
-      if solve_result.0.require > 0 || solve_result.0.overflow > 0 {
+      if count == 1 {
+        print!("结果:");
+      } else {
+        print!("结果 {}:", idx + 1);
+      }
+      if total.require > 0 || total.overflow > 0 {
         print!(" (");
-        if solve_result.0.require > 0 {
-          print!(" 差距总和: {}", solve_result.0.require);
+        if total.require > 0 {
+          print!(" 差距总和: {}", total.require);
         }
-        if solve_result.0.overflow > 0 {
-          print!(" 溢出总和: {}", solve_result.0.overflow);
+        if total.overflow > 0 {
+          print!(" 溢出总和: {}", total.overflow);
         }
         print!(" )")
       }
+      if self.objective != Objective::Lexicographic {
+        print!(" 目标值: {}", score.primary);
+      }
       println!();
 
-      for (zone, members) in solve_result.1 {
-        let attr = zone.calc_detail(&members);
+      // in the order of the zones added
+      for zone in &self.zones {
+        let Some(members) = assignment.get(zone) else {
+          continue;
+        };
+        let attr = zone.calc_detail(members);
         indent(2);
         println!("{}: ", zone.name);
         indent(4);
@@ -54,14 +82,13 @@ impl State {
         println_attr(4, &"科普价值", attr.value, zone.require.value);
         println_attr(4, &"吸引人流", attr.popularity, zone.require.popularity);
       }
-    } else {
-      println!("计算失败");
     }
   }
 
   pub fn clear(&mut self) {
     self.members.clear();
     self.zones.clear();
+    self.constraints = Constraints::default();
   }
 
   pub fn print(&self) {
@@ -102,12 +129,42 @@ impl State {
         println!("科普物料: {}", sub_level.value);
         indent(4);
         println!("游客宣传: {}", sub_level.popularity);
+        indent(4);
+        println!("助理数量: {}-{}", zone.min_assistants, zone.max_assistants);
       }
     }
 
     if self.members.is_empty() && self.zones.is_empty() {
       println!("[空]");
     }
+
+    if !self.constraints.is_empty() {
+      println!("限制: ");
+      let Constraints {
+        pinned,
+        excluded,
+        forbidden_pairs,
+        required_pairs,
+      } = &self.constraints;
+      for (member, zone) in pinned {
+        indent(2);
+        println!("{member} 在 {zone}");
+      }
+      for member in excluded {
+        indent(2);
+        println!("{member} 不参与");
+      }
+      for (a, b) in forbidden_pairs {
+        indent(2);
+        println!("{a} 与 {b} 不在同一区域");
+      }
+      for (a, b) in required_pairs {
+        indent(2);
+        println!("{a} 与 {b} 在同一区域");
+      }
+    }
+
+    println!("目标: {}", objective_name(&self.objective));
   }
 
   pub fn add_member(&mut self, name: impl Into<String>, attr: Attribute) {
@@ -127,12 +184,14 @@ impl State {
     base: Attribute,
     sub_level: Attribute,
     require: Attribute,
+    (min_assistants, max_assistants): (usize, usize),
   ) {
     let name = name.into();
     self.del_zone(&name);
-    self
-      .zones
-      .push(Zone::new(name, base, sub_level, require, 100))
+    self.zones.push(
+      Zone::new(name, base, sub_level, require, 100)
+        .with_assistants(min_assistants, max_assistants),
+    )
   }
 
   pub fn del_zone(&mut self, name: impl Into<String>) {
@@ -175,6 +234,28 @@ impl State {
     }
   }
 
+  pub fn update_zone_assistants(
+    &mut self,
+    name: impl Into<String>,
+    (min, max): (usize, usize),
+  ) -> Result<(), bool> {
+    if let Some(zone) = self.zone_by_name_mut(name) {
+      zone.min_assistants = min;
+      zone.max_assistants = max;
+      Ok(())
+    } else {
+      unknown_usage()
+    }
+  }
+
+  fn has_member(&self, name: &str) -> bool {
+    self.members.iter().any(|it| it.name == name)
+  }
+
+  fn has_zone(&self, name: &str) -> bool {
+    self.zones.iter().any(|it| it.name == name)
+  }
+
   fn zone_by_name_mut(&mut self, name: impl Into<String>) -> Option<&mut Zone> {
     let name = name.into();
     self.zones.iter_mut().find(|it| it.name == name)
@@ -230,25 +311,35 @@ fn loop_once(state: &mut State, refresh: &mut bool) -> Result<(), bool> {
     "delm" => {
       check_arg(&args, 1)?;
       state.del_member(&args[0]);
+      state.constraints.remove_member(&args[0]);
     }
 
     "addz" => {
-      check_arg(&args, 7)?;
+      if args.len() != 7 && args.len() != 9 {
+        return unknown_usage();
+      }
       if args[1] != "base" || args[3] != "lvl" || args[5] != "req" {
         return unknown_usage();
       }
+      let assistants = match args.get(7..) {
+        Some([key, value]) if key == "slots" => parse_assistants(value)?,
+        Some([]) => (1, 3),
+        _ => return unknown_usage(),
+      };
 
       state.add_zone(
         &args[0],
         parse_attr(&args[2])?,
         parse_attr(&args[4])?,
         parse_attr(&args[6])?,
+        assistants,
       )
     }
     "delz" => {
       check_arg(&args, 1)?;
 
       state.del_zone(&args[0]);
+      state.constraints.remove_zone(&args[0]);
     }
     "updz" => {
       check_arg(&args, 3)?;
@@ -257,14 +348,61 @@ fn loop_once(state: &mut State, refresh: &mut bool) -> Result<(), bool> {
         "lvl" => state.update_zone_level(&args[0], parse_attr(&args[2])?),
         "req" => state.update_zone_require(&args[0], parse_attr(&args[2])?),
         "scaler" => state.update_zone_scaler(&args[0], parse_i64(&args[2])? as u64),
+        "slots" => state.update_zone_assistants(&args[0], parse_assistants(&args[2])?),
         _ => return unknown_usage(),
       }?;
     }
 
+    "pin" => match (parse_pair(state, args.first())?, args.get(1)) {
+      (Some((a, b)), None) => state.constraints.require_pair(a, b),
+      (None, Some(zone)) if state.has_member(&args[0]) && state.has_zone(zone) => {
+        state.constraints.pin(&args[0], zone)
+      }
+      _ => return unknown_usage(),
+    },
+    "unpin" => {
+      check_arg(&args, 1)?;
+
+      match parse_pair(state, args.first())? {
+        Some((a, b)) => state.constraints.remove_pair(a, b),
+        None => state.constraints.remove_member(&args[0]),
+      }
+    }
+    "ban" => {
+      check_arg(&args, 1)?;
+
+      match parse_pair(state, args.first())? {
+        Some((a, b)) => state.constraints.forbid_pair(a, b),
+        None if state.has_member(&args[0]) => state.constraints.exclude(&args[0]),
+        None => return unknown_usage(),
+      }
+    }
+
+    "obj" => {
+      state.objective = match (args.first().map(String::as_str), args.get(1)) {
+        (Some("lex"), None) => Objective::Lexicographic,
+        (Some("sum"), Some(weights)) => match parse_weights(weights)?[..] {
+          [require, overflow] => Objective::WeightedSum { require, overflow },
+          _ => return unknown_usage(),
+        },
+        (Some("minmax"), None) => Objective::MinMaxShortfall,
+        (Some("satisfied"), None) => Objective::MaxSatisfied,
+        (Some("stat"), Some(weights)) => match parse_weights(weights)?[..] {
+          [time, value, popularity] => Objective::StatWeights {
+            time,
+            value,
+            popularity,
+          },
+          _ => return unknown_usage(),
+        },
+        _ => return unknown_usage(),
+      };
+    }
+
     "load" => {
       let result = state.load(PathBuf::from(
         &args
-          .get(0)
+          .first()
           .cloned()
           .unwrap_or_else(|| String::from("state.json")),
       ));
@@ -277,7 +415,7 @@ fn loop_once(state: &mut State, refresh: &mut bool) -> Result<(), bool> {
     "save" => {
       state.save(PathBuf::from(
         &args
-          .get(0)
+          .first()
           .cloned()
           .unwrap_or_else(|| String::from("state.json")),
       ));
@@ -287,7 +425,15 @@ fn loop_once(state: &mut State, refresh: &mut bool) -> Result<(), bool> {
     "clear" => state.clear(),
 
     "solve" => {
-      state.solve_print();
+      let count = match args.first() {
+        Some(count) => parse_i64(count)?,
+        None => 1,
+      };
+      if !(1..=MAX_SOLUTIONS as i64).contains(&count) {
+        return unknown_usage();
+      }
+
+      state.solve_print(count as usize);
       *refresh = false;
     }
 
@@ -334,6 +480,44 @@ fn parse_attr(input: impl AsRef<str>) -> Result<Attribute, bool> {
   Ok(Attribute::new(attr[0], attr[1], attr[2]))
 }
 
+/// least and most assistants of a zone, at least one
+fn parse_assistants(input: impl AsRef<str>) -> Result<(usize, usize), bool> {
+  let assistants = input
+    .as_ref()
+    .split('/')
+    .map(parse_i64)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  match assistants[..] {
+    [min, max] if 1 <= min && min <= max => Ok((min as usize, max as usize)),
+    _ => Err(unknown_usage().unwrap_err()),
+  }
+}
+
+/// two members of the state as <名字>+<名字>, None if not a pair
+fn parse_pair<'a>(
+  state: &State,
+  input: Option<&'a String>,
+) -> Result<Option<(&'a str, &'a str)>, bool> {
+  let Some((a, b)) = input.and_then(|it| it.split_once('+')) else {
+    return Ok(None);
+  };
+
+  if a == b || !state.has_member(a) || !state.has_member(b) {
+    return Err(unknown_usage().unwrap_err());
+  }
+
+  Ok(Some((a, b)))
+}
+
+fn parse_weights(input: impl AsRef<str>) -> Result<Vec<u64>, bool> {
+  input
+    .as_ref()
+    .split('/')
+    .map(|it| u64::from_str(it).map_err(|_| unknown_usage().unwrap_err()))
+    .collect()
+}
+
 fn parse_i64(input: impl AsRef<str>) -> Result<i64, bool> {
   i64::from_str(input.as_ref()).map_err(|_| unknown_usage().unwrap_err())
 }
@@ -362,20 +546,21 @@ fn print_help() {
   println!(
     r#"
 命令:
-  solve
-    计算结果
+  solve <数量>
+    计算结果, 给出数量时列出最好的几种不同方案 默认 1, 最多 100
 
   addm <名字> <游览时长>/<科普价值>/<吸引人流>
     添加助理
   delm <名字>
     移除助理
 
-  addz <名字> base <游览时长>/<科普价值>/<吸引人流> lvl <游览导引>/<科普物料>/<游客宣传> req <游览时长>/<科普价值>/<吸引人流>
+  addz <名字> base <游览时长>/<科普价值>/<吸引人流> lvl <游览导引>/<科普物料>/<游客宣传> req <游览时长>/<科普价值>/<吸引人流> [slots <最少>/<最多>]
     添加区域
       base 后方为基础数值, 可通过 "无助理时的值 - 相应等级 * 10" 计算得出
       lvl 后方为当前的等级
       req 后方为需要的数值
-      例: addz 综合区-内 base 80/80/80 lvl 3/6/2 req 205/245/150
+      slots 后方为助理数量, 默认 1/3, 仅在剩余助理少于最少数量时区域才会空着
+      例: addz 综合区-内 base 80/80/80 lvl 3/6/2 req 205/245/150 slots 1/3
   delz <名字>
     移除区域
   updz <名字> lvl <游览导引>/<科普物料>/<游客宣传>
@@ -384,6 +569,32 @@ fn print_help() {
     更新区域需求
   updz <名字> scaler <百分比>
     更新区域基础数值缩放
+  updz <名字> slots <最少>/<最多>
+    更新区域助理数量
+
+  pin <名字> <区域名字>
+    限制助理只能在该区域
+  pin <名字>+<名字>
+    限制两名助理必须在同一区域
+  ban <名字>
+    限制助理不参与
+  ban <名字>+<名字>
+    限制两名助理不在同一区域
+  unpin <名字>
+    移除该助理的所有限制
+  unpin <名字>+<名字>
+    移除两名助理之间的限制
+
+  obj lex
+    目标: 先使差距总和最小, 再使溢出总和最小 (默认)
+  obj sum <差距权重>/<溢出权重>
+    目标: 使加权后的差距与溢出之和最小
+  obj minmax
+    目标: 使差距最大的区域的差距最小
+  obj satisfied
+    目标: 使满足需求的区域最多, 再使差距总和最小
+  obj stat <游览时长权重>/<科普价值权重>/<吸引人流权重>
+    目标: 使各项差距加权之和最小, 再使溢出总和最小
 
   load <路径>
     从文件加载状态 默认 "state.json" (启动时自动尝试从 "state.json" 加载)
@@ -403,6 +614,22 @@ fn print_help_short() {
   println!("输入 help 查看帮助");
 }
 
+fn objective_name(objective: &Objective) -> String {
+  match objective {
+    Objective::Lexicographic => "差距总和, 溢出总和".to_string(),
+    Objective::WeightedSum { require, overflow } => {
+      format!("差距 x{require} + 溢出 x{overflow}")
+    }
+    Objective::MinMaxShortfall => "最大差距".to_string(),
+    Objective::MaxSatisfied => "未满足的区域".to_string(),
+    Objective::StatWeights {
+      time,
+      value,
+      popularity,
+    } => format!("游览时长差距 x{time} + 科普价值差距 x{value} + 吸引人流差距 x{popularity}"),
+  }
+}
+
 fn indent(num: usize) {
   print!("{0:>1$}", "", num);
 }
@@ -453,6 +680,11 @@ impl Default for State {
       ),
     ];
 
-    Self { members, zones }
+    Self {
+      members,
+      zones,
+      objective: Objective::default(),
+      constraints: Constraints::default(),
+    }
   }
 }
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{MemberInfo, Zone};
+
+/// what [crate::solve_with] minimises, scores of zones are compared by primary then secondary
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+  /// total shortfall, then total overflow
+  #[default]
+  Lexicographic,
+  /// total shortfall and total overflow multiplied by the weights and added together
+  WeightedSum { require: u64, overflow: u64 },
+  /// the largest shortfall of a zone, ties are not broken
+  /// as the solver only keeps the best of the remaining zones
+  MinMaxShortfall,
+  /// zones with any shortfall, then total shortfall
+  MaxSatisfied,
+  /// shortfall of each stat multiplied by its weight, then total overflow
+  StatWeights {
+    time: u64,
+    value: u64,
+    popularity: u64,
+  },
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Score {
+  pub primary: u64,
+  pub secondary: u64,
+}
+
+impl Score {
+  pub fn new(primary: u64, secondary: u64) -> Self {
+    Self { primary, secondary }
+  }
+}
+
+impl Objective {
+  /// score of a zone with the members, or left empty
+  pub fn score(&self, zone: &Zone, members: &[MemberInfo]) -> Score {
+    let result = zone.calc(members);
+
+    match *self {
+      Objective::Lexicographic => Score::new(result.require, result.overflow),
+      Objective::WeightedSum { require, overflow } => Score::new(
+        result
+          .require
+          .saturating_mul(require)
+          .saturating_add(result.overflow.saturating_mul(overflow)),
+        0,
+      ),
+      Objective::MinMaxShortfall => Score::new(result.require, 0),
+      Objective::MaxSatisfied => Score::new(u64::from(result.require > 0), result.require),
+      Objective::StatWeights {
+        time,
+        value,
+        popularity,
+      } => {
+        let shortfall = zone.shortfall(members);
+        let weighted = |short: i64, weight: u64| (short.max(0) as u64).saturating_mul(weight);
+
+        Score::new(
+          weighted(shortfall.time, time)
+            .saturating_add(weighted(shortfall.value, value))
+            .saturating_add(weighted(shortfall.popularity, popularity)),
+          result.overflow,
+        )
+      }
+    }
+  }
+
+  /// score of zones together, never less than either of them
+  /// and never decreasing when either of them increases, so the best of the remaining zones
+  /// gives the best in total
+  pub fn combine(&self, a: Score, b: Score) -> Score {
+    match self {
+      Objective::MinMaxShortfall => Score::new(
+        a.primary.max(b.primary),
+        a.secondary.saturating_add(b.secondary),
+      ),
+      _ => Score::new(
+        a.primary.saturating_add(b.primary),
+        a.secondary.saturating_add(b.secondary),
+      ),
+    }
+  }
+}
@@ -0,0 +1,404 @@
+use itertools::Itertools;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use museum_solver::{
+  solve, solve_top_k, solve_with, Attribute, CalcResult, Constraints, MemberInfo, Objective, Score,
+  Zone,
+};
+
+fn attribute(rng: &mut ChaCha8Rng, max: i64) -> Attribute {
+  Attribute::new(
+    rng.gen_range(0..max),
+    rng.gen_range(0..max),
+    rng.gen_range(0..max),
+  )
+}
+
+fn roster(rng: &mut ChaCha8Rng, members: usize, zones: usize) -> (Vec<MemberInfo>, Vec<Zone>) {
+  let members = (0..members)
+    .map(|idx| MemberInfo::new(format!("member {idx}"), attribute(rng, 60)))
+    .collect_vec();
+  let zones = (0..zones)
+    .map(|idx| {
+      Zone::new(
+        format!("zone {idx}"),
+        attribute(rng, 20),
+        attribute(rng, 5),
+        attribute(rng, 250),
+        100 + rng.gen_range(0..3) * 25,
+      )
+      .with_assistants(rng.gen_range(1..=2), rng.gen_range(2..=3))
+    })
+    .collect_vec();
+
+  (members, zones)
+}
+
+/// try every way to fill the zones, or leave them empty if too few members are left
+fn brute_force(members: &[MemberInfo], zones: &[Zone], objective: &Objective) -> Score {
+  let Some(zone) = zones.first() else {
+    return Score::default();
+  };
+
+  zone
+    .team_sizes(members.len())
+    .flat_map(|size| members.iter().cloned().combinations(size))
+    .map(|picked| {
+      let rest = members
+        .iter()
+        .filter(|it| !picked.contains(it))
+        .cloned()
+        .collect_vec();
+
+      objective.combine(
+        objective.score(zone, &picked),
+        brute_force(&rest, &zones[1..], objective),
+      )
+    })
+    .min()
+    .expect("a team or an empty zone at least")
+}
+
+/// scores of every way to fill the zones, or leave them empty if too few members are left
+fn all_scores(members: &[MemberInfo], zones: &[Zone], objective: &Objective) -> Vec<Score> {
+  let Some(zone) = zones.first() else {
+    return vec![Score::default()];
+  };
+
+  zone
+    .team_sizes(members.len())
+    .flat_map(|size| members.iter().cloned().combinations(size))
+    .flat_map(|picked| {
+      let rest = members
+        .iter()
+        .filter(|it| !picked.contains(it))
+        .cloned()
+        .collect_vec();
+      let score = objective.score(zone, &picked);
+
+      all_scores(&rest, &zones[1..], objective)
+        .into_iter()
+        .map(move |it| objective.combine(score, it))
+    })
+    .collect_vec()
+}
+
+#[test]
+fn solve_matches_brute_force() {
+  let mut rng = ChaCha8Rng::seed_from_u64(42);
+
+  for members in 0..=9 {
+    for zones in 1..=3 {
+      for _ in 0..5 {
+        let (members, zones) = roster(&mut rng, members, zones);
+
+        let (result, assignment) = solve(members.clone(), zones.clone()).unwrap();
+        let best = brute_force(&members, &zones, &Objective::Lexicographic);
+        assert_eq!(result, CalcResult::new(best.primary, best.secondary));
+
+        // the assignment gives the total it claims, each member at most once, every zone in it
+        let total = assignment
+          .iter()
+          .map(|(zone, members)| zone.calc(members))
+          .fold(CalcResult::new(0, 0), |acc, it| acc + it);
+        assert_eq!(total, result);
+        assert!(assignment.values().flatten().all_unique());
+        assert_eq!(assignment.len(), zones.len());
+        assert!(assignment.iter().all(|(zone, members)| members.is_empty()
+          || (zone.min_assistants..=zone.max_assistants).contains(&members.len())));
+        // left empty only if too few members are left after the zones before
+        let mut available = members.len();
+        for zone in &zones {
+          let team = &assignment[zone];
+          assert!(!team.is_empty() || available < zone.min_assistants);
+          available -= team.len();
+        }
+      }
+    }
+  }
+}
+
+/// a zone is only left empty if fewer members than its least assistants are left for it
+#[test]
+fn min_assistants_is_a_minimum() {
+  let members = (0..3)
+    .map(|idx| MemberInfo::new(format!("member {idx}"), Attribute::new(10, 10, 10)))
+    .collect_vec();
+  let zero = || Attribute::new(0, 0, 0);
+  // any member only overflows the first zone, the second needs every member
+  let met = Zone::new("met", zero(), zero(), zero(), 100).with_assistants(2, 3);
+  let needy =
+    Zone::new("needy", zero(), zero(), Attribute::new(100, 100, 100), 100).with_assistants(1, 3);
+
+  let (_, assignment) = solve(members.clone(), vec![met.clone(), needy.clone()]).unwrap();
+  assert_eq!(assignment[&met].len(), 2);
+  assert_eq!(assignment[&needy].len(), 1);
+
+  // 1 member left after the first zone, not enough for the second
+  let first = needy.clone().with_assistants(1, 1);
+  let second = needy.with_assistants(2, 3);
+  let (_, assignment) = solve(members[..2].to_vec(), vec![first.clone(), second.clone()]).unwrap();
+  assert_eq!(assignment[&first].len(), 1);
+  assert!(assignment[&second].is_empty());
+}
+
+#[test]
+fn solve_with_matches_brute_force() {
+  let objectives = [
+    Objective::Lexicographic,
+    Objective::WeightedSum {
+      require: 3,
+      overflow: 1,
+    },
+    Objective::MinMaxShortfall,
+    Objective::MaxSatisfied,
+    Objective::StatWeights {
+      time: 1,
+      value: 4,
+      popularity: 2,
+    },
+  ];
+  let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+  for members in 0..=8 {
+    for zones in 1..=3 {
+      for _ in 0..3 {
+        let (members, zones) = roster(&mut rng, members, zones);
+
+        for objective in &objectives {
+          let (score, assignment) = solve_with(
+            members.clone(),
+            zones.clone(),
+            objective,
+            &Constraints::default(),
+          )
+          .unwrap();
+          assert_eq!(score, brute_force(&members, &zones, objective));
+
+          let total = assignment
+            .iter()
+            .map(|(zone, members)| objective.score(zone, members))
+            .fold(Score::default(), |acc, it| objective.combine(acc, it));
+          assert_eq!(total, score);
+        }
+      }
+    }
+  }
+}
+
+#[test]
+fn solve_top_k_matches_brute_force() {
+  let objectives = [Objective::Lexicographic, Objective::MinMaxShortfall];
+  let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+  for members in 0..=7 {
+    for zones in 1..=3 {
+      let (members, zones) = roster(&mut rng, members, zones);
+
+      for (objective, k) in objectives.iter().cartesian_product([1, 4, 30]) {
+        let results = solve_top_k(
+          members.clone(),
+          zones.clone(),
+          objective,
+          &Constraints::default(),
+          k,
+        );
+
+        let expected = all_scores(&members, &zones, objective)
+          .into_iter()
+          .sorted()
+          .take(k)
+          .collect_vec();
+        assert_eq!(results.iter().map(|it| it.0).collect_vec(), expected);
+
+        // different assignments, each with the score it claims
+        assert!(results
+          .iter()
+          .map(|it| it
+            .1
+            .iter()
+            .map(|(zone, members)| (&zone.name, members))
+            .sorted()
+            .collect_vec())
+          .all_unique());
+        for (score, assignment) in &results {
+          let total = assignment
+            .iter()
+            .map(|(zone, members)| objective.score(zone, members))
+            .fold(Score::default(), |acc, it| objective.combine(acc, it));
+          assert_eq!(total, *score);
+        }
+      }
+    }
+  }
+}
+
+/// teams of every way to fill the zones, or leave them empty if too few members are left
+fn all_assignments(members: &[MemberInfo], zones: &[Zone]) -> Vec<Vec<Vec<MemberInfo>>> {
+  let Some(zone) = zones.first() else {
+    return vec![vec![]];
+  };
+
+  zone
+    .team_sizes(members.len())
+    .flat_map(|size| members.iter().cloned().combinations(size))
+    .flat_map(|picked| {
+      let rest = members
+        .iter()
+        .filter(|it| !picked.contains(it))
+        .cloned()
+        .collect_vec();
+
+      all_assignments(&rest, &zones[1..])
+        .into_iter()
+        .map(move |mut it| {
+          it.insert(0, picked.clone());
+          it
+        })
+    })
+    .collect_vec()
+}
+
+/// if the teams of the zones follow the rules, checked on the whole assignment
+fn satisfies(
+  constraints: &Constraints,
+  roster: &[MemberInfo],
+  zones: &[Zone],
+  teams: &[Vec<MemberInfo>],
+) -> bool {
+  let zone_of = |name: &String| {
+    teams
+      .iter()
+      .position(|team| team.iter().any(|it| &it.name == name))
+  };
+  let in_roster = |name: &String| roster.iter().any(|it| &it.name == name);
+
+  roster.iter().all(|member| {
+    let zone = zone_of(&member.name);
+    (!constraints.excluded.contains(&member.name) || zone.is_none())
+      && constraints
+        .pinned
+        .get(&member.name)
+        .is_none_or(|pinned| zone.is_some_and(|it| &zones[it].name == pinned))
+  }) && constraints
+    .forbidden_pairs
+    .iter()
+    .all(|(a, b)| zone_of(a).is_none() || zone_of(a) != zone_of(b))
+    && constraints
+      .required_pairs
+      .iter()
+      .filter(|(a, b)| in_roster(a) && in_roster(b))
+      .all(|(a, b)| zone_of(a).is_some() && zone_of(a) == zone_of(b))
+}
+
+/// a pair with a member not in the roster is ignored, an excluded member still conflicts
+#[test]
+fn pairs_out_of_roster_are_ignored() {
+  let (members, zones) = roster(&mut ChaCha8Rng::seed_from_u64(11), 4, 2);
+  let objective = Objective::Lexicographic;
+  let expected = solve_with(
+    members.clone(),
+    zones.clone(),
+    &objective,
+    &Constraints::default(),
+  );
+  assert!(expected.is_some());
+
+  let mut constraints = Constraints::default();
+  constraints.require_pair("member 0", "ghost");
+  constraints.forbid_pair("member 1", "ghost");
+  let result = solve_with(members.clone(), zones.clone(), &objective, &constraints);
+  assert_eq!(result.map(|it| it.0), expected.map(|it| it.0));
+
+  constraints.exclude("member 2");
+  constraints.require_pair("member 0", "member 2");
+  assert_eq!(solve_with(members, zones, &objective, &constraints), None);
+}
+
+/// k only bounds the results kept, a huge k lists every assignment
+#[test]
+fn solve_top_k_with_huge_k() {
+  let (members, zones) = roster(&mut ChaCha8Rng::seed_from_u64(7), 4, 2);
+  let objective = Objective::Lexicographic;
+  let constraints = Constraints::default();
+
+  let every = solve_top_k(
+    members.clone(),
+    zones.clone(),
+    &objective,
+    &constraints,
+    1 << 40,
+  );
+  assert_eq!(every.len(), all_assignments(&members, &zones).len());
+  assert_eq!(
+    solve_top_k(members, zones, &objective, &constraints, usize::MAX).len(),
+    every.len()
+  );
+}
+
+#[test]
+fn solve_top_k_with_constraints_matches_brute_force() {
+  let objective = Objective::Lexicographic;
+  let mut rng = ChaCha8Rng::seed_from_u64(3);
+
+  for members in 2..=7 {
+    for zones in 1..=3 {
+      for _ in 0..6 {
+        let (roster, zones) = roster(&mut rng, members, zones);
+
+        // a few random rules, on members not in the roster too
+        let mut constraints = Constraints::default();
+        let name = |rng: &mut ChaCha8Rng| format!("member {}", rng.gen_range(0..=members));
+        for _ in 0..rng.gen_range(0..3) {
+          let zone = format!("zone {}", rng.gen_range(0..zones.len()));
+          constraints.pin(name(&mut rng), zone);
+        }
+        for _ in 0..rng.gen_range(0..2) {
+          constraints.exclude(name(&mut rng));
+        }
+        for _ in 0..rng.gen_range(0..3) {
+          constraints.forbid_pair(name(&mut rng), name(&mut rng));
+        }
+        for _ in 0..rng.gen_range(0..2) {
+          constraints.require_pair(name(&mut rng), name(&mut rng));
+        }
+
+        let results = solve_top_k(roster.clone(), zones.clone(), &objective, &constraints, 3);
+
+        // excluded members are not left for any zone
+        let assignable = roster
+          .iter()
+          .filter(|it| !constraints.excluded.contains(&it.name))
+          .cloned()
+          .collect_vec();
+        let expected = all_assignments(&assignable, &zones)
+          .into_iter()
+          .filter(|it| satisfies(&constraints, &roster, &zones, it))
+          .map(|teams| {
+            zones
+              .iter()
+              .zip(&teams)
+              .map(|(zone, team)| objective.score(zone, team))
+              .fold(Score::default(), |acc, it| objective.combine(acc, it))
+          })
+          .sorted()
+          .take(3)
+          .collect_vec();
+        assert_eq!(
+          results.iter().map(|it| it.0).collect_vec(),
+          expected,
+          "{constraints:?}"
+        );
+
+        for (_, assignment) in &results {
+          let teams = zones
+            .iter()
+            .map(|it| assignment.get(it).cloned().unwrap_or_default())
+            .collect_vec();
+          assert!(satisfies(&constraints, &roster, &zones, &teams));
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,106 @@
+//! generate the solution table for [src/table.rs] when the `table` feature is enabled
+//!
+//! covers every compass with 3 rings of 6 positions, where each ring moves 1, 2 or 3
+//! positions per rotation in either direction, with any set of linkages,
+//! for every value to move (target - current, times direction), the table gives
+//! the first linkage of a shortest solution, 4 bits for each entry
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+const POSITIONS: usize = 6;
+const STATES: usize = POSITIONS * POSITIONS * POSITIONS;
+/// linkages 001 to 111
+const MASKS: usize = 7;
+const SUBSETS: usize = 1 << MASKS;
+
+const SOLVED: u8 = 0;
+const UNSOLVABLE: u8 = 0xF;
+
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+
+  let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("table.bin");
+
+  if std::env::var_os("CARGO_FEATURE_TABLE").is_none() {
+    std::fs::write(out, []).expect("failed to write table");
+    return;
+  }
+
+  // indexed by nums, moves, then subset of masks
+  let mut table = vec![0_u8; 27 * STATES * SUBSETS / 2];
+
+  for nums_index in 0..27 {
+    let nums = [
+      nums_index / 9 + 1,
+      nums_index / 3 % 3 + 1,
+      nums_index % 3 + 1,
+    ];
+
+    for subset in 0..SUBSETS {
+      let masks = (1..=MASKS)
+        .filter(|mask| subset & (1 << (mask - 1)) > 0)
+        .collect::<Vec<_>>();
+      let distance = distance(&nums, &masks);
+
+      for moves in 0..STATES {
+        let entry = first_step(&nums, &masks, &distance, moves);
+
+        let index = (nums_index * STATES + moves) * SUBSETS + subset;
+        table[index / 2] |= entry << (index % 2 * 4);
+      }
+    }
+  }
+
+  std::fs::write(out, table).expect("failed to write table");
+}
+
+/// index of the state after one rotation with mask
+fn step(nums: &[usize; 3], state: usize, mask: usize) -> usize {
+  let mut values = [state / 36, state / 6 % 6, state % 6];
+  for (idx, value) in values.iter_mut().enumerate() {
+    if mask & (0b100 >> idx) > 0 {
+      *value = (*value + nums[idx]) % POSITIONS;
+    }
+  }
+  values[0] * 36 + values[1] * 6 + values[2]
+}
+
+/// least rotations to move the rings from zero to every state,
+/// same as moving from the state back to zero, as rotations commute
+fn distance(nums: &[usize; 3], masks: &[usize]) -> [usize; STATES] {
+  let mut distance = [usize::MAX; STATES];
+  distance[0] = 0;
+
+  let mut queue = VecDeque::from([0]);
+  while let Some(state) = queue.pop_front() {
+    for mask in masks {
+      let next = step(nums, state, *mask);
+      if distance[next] == usize::MAX {
+        distance[next] = distance[state] + 1;
+        queue.push_back(next);
+      }
+    }
+  }
+
+  distance
+}
+
+/// the first mask on a shortest way to move the rings by moves
+fn first_step(nums: &[usize; 3], masks: &[usize], distance: &[usize; STATES], moves: usize) -> u8 {
+  if moves == 0 {
+    return SOLVED;
+  }
+  if distance[moves] == usize::MAX {
+    return UNSOLVABLE;
+  }
+
+  // after rotating with the mask, what's left to move must be one step closer
+  *masks
+    .iter()
+    .find(|mask| {
+      let rest = (0..POSITIONS - 1).fold(moves, |acc, _| step(nums, acc, **mask));
+      distance[rest] + 1 == distance[moves]
+    })
+    .unwrap() as u8
+}
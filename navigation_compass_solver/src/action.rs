@@ -5,8 +5,8 @@ pub enum Action {
   Rotate(Linkage),
 }
 
-impl std::fmt::Debug for Action {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Action {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     match self {
       Action::Rotate(linkage) => linkage.fmt(f),
     }
@@ -0,0 +1,20 @@
+use alloc::vec::Vec;
+
+use crate::linkage::Linkage;
+
+/// what the linkages can do with a compass, from [crate::navigation_compass::NavigationCompass::analyze]
+#[derive(Debug, Clone)]
+pub struct Analysis {
+  /// every state the rings can get to, current value of every ring, in order
+  pub reachable: Vec<Vec<i8>>,
+  /// least rotations to each reachable state, in the same order
+  pub rotations: Vec<u32>,
+  /// is the target one of the reachable states
+  pub solvable: bool,
+  /// values never reached by each ring
+  pub unreachable_values: Vec<Vec<i8>>,
+  /// a linkage with the fewest rings that makes the puzzle solvable when added,
+  /// None if already solvable, no single linkage can help or there are too many rings
+  /// or states to try
+  pub suggestion: Option<Linkage>,
+}
@@ -0,0 +1,65 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{action::Action, puzzle::CompassPuzzle};
+
+/// a catalogue of puzzles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PuzzleFile {
+  pub puzzles: Vec<NamedPuzzle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedPuzzle {
+  pub name: String,
+  #[serde(flatten)]
+  pub puzzle: CompassPuzzle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultFile {
+  pub results: Vec<PuzzleResult>,
+}
+
+/// solution of a puzzle, either solution or error is present
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PuzzleResult {
+  pub name: String,
+  /// puzzle in one line
+  pub puzzle: String,
+  /// linkage for every step
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub solution: Option<Vec<String>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+impl PuzzleFile {
+  /// solve every puzzle, in the same order
+  pub fn solve_all(&self) -> ResultFile {
+    let results = self
+      .puzzles
+      .iter()
+      .map(|NamedPuzzle { name, puzzle }| {
+        let result = puzzle.compass.try_solve(puzzle.linkages.clone());
+
+        PuzzleResult {
+          name: name.clone(),
+          puzzle: puzzle.to_string(),
+          solution: result.as_ref().ok().map(|it| {
+            it.iter()
+              .map(|it| match it {
+                Action::Rotate(linkage) => linkage.to_string(),
+              })
+              .collect()
+          }),
+          error: result.err().map(|it| it.to_string()),
+        }
+      })
+      .collect();
+
+    ResultFile { results }
+  }
+}
@@ -0,0 +1,45 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::SolveError;
+
+/// clicks spent in game, every list is in the same order as the linkages
+#[derive(Debug, Clone)]
+pub struct Cost {
+  /// clicks for each rotation
+  pub rotate: Vec<u32>,
+  /// clicks to switch to the linkage, paid once as actions with the same linkage are grouped
+  pub switch: Vec<u32>,
+}
+
+impl Cost {
+  pub fn new(rotate: Vec<u32>, switch: Vec<u32>) -> Self {
+    Self { rotate, switch }
+  }
+
+  /// same cost for every linkage
+  pub fn uniform(linkages: usize, rotate: u32, switch: u32) -> Self {
+    Self::new(vec![rotate; linkages], vec![switch; linkages])
+  }
+
+  /// clicks to rotate count times with the linkage at index
+  pub fn of(&self, linkage: usize, count: u32) -> u64 {
+    if count == 0 {
+      0
+    } else {
+      self.switch[linkage] as u64 + self.rotate[linkage] as u64 * count as u64
+    }
+  }
+
+  pub fn check(&self, linkages: usize) -> Result<(), SolveError> {
+    if self.rotate.len() != linkages || self.switch.len() != linkages {
+      return Err(SolveError::CostMismatch {
+        rotate: self.rotate.len(),
+        switch: self.switch.len(),
+        linkages,
+      });
+    }
+
+    Ok(())
+  }
+}
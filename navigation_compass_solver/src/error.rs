@@ -0,0 +1,149 @@
+use alloc::string::String;
+
+use crate::linkage::Linkage;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+  /// no combination of the linkages gets every ring to its target
+  Unsolvable,
+  /// gave up after reaching more states than the limit
+  SearchLimitExceeded {
+    limit: usize,
+  },
+
+  /// compass without ring
+  NoRing,
+  /// ring description can't be parsed
+  InvalidRing(String),
+  /// number of positions not in 1..=128
+  InvalidPositions {
+    ring: usize,
+    positions: u8,
+  },
+  /// direction other than 1 or -1
+  InvalidDirection {
+    ring: usize,
+    direction: i8,
+  },
+  CurrentOutOfRange {
+    ring: usize,
+    current: i8,
+    positions: u8,
+  },
+  TargetOutOfRange {
+    ring: usize,
+    target: i8,
+    positions: u8,
+  },
+  /// target list can't be parsed or has a different number of rings
+  InvalidTarget(String),
+
+  /// puzzle notation can't be parsed
+  InvalidPuzzle(String),
+
+  EmptyLinkages,
+  /// linkage description can't be parsed
+  InvalidLinkage(String),
+  /// linkage has a different number of rings
+  LinkageWidthMismatch {
+    linkage: Linkage,
+    rings: usize,
+  },
+  DuplicateLinkage(Linkage),
+  /// override of a ring past the number of rings of the linkage
+  OverrideOutOfRange {
+    ring: usize,
+    width: u8,
+  },
+  /// more rings than a linkage can hold
+  TooManyRings {
+    rings: usize,
+  },
+  /// more distinct linkages than the rings can form
+  TooManyLinkages {
+    linkages: usize,
+    rings: usize,
+  },
+  /// zero solutions asked for when listing solutions
+  NoSolutionAsked,
+  /// cost list can't be parsed
+  InvalidCost(String),
+  /// number of costs doesn't match the linkages
+  CostMismatch {
+    rotate: usize,
+    switch: usize,
+    linkages: usize,
+  },
+}
+
+impl core::fmt::Display for SolveError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      SolveError::Unsolvable => write!(f, "no solution"),
+      SolveError::SearchLimitExceeded { limit } => {
+        write!(f, "failed to solve, more than {limit} states to search")
+      }
+
+      SolveError::NoRing => write!(f, "at least one ring is required"),
+      SolveError::InvalidRing(raw) => write!(f, "failed to parse ring: {raw}"),
+      SolveError::InvalidPositions { ring, positions } => write!(
+        f,
+        "ring {ring} has {positions} positions, should be in 1..=128"
+      ),
+      SolveError::InvalidDirection { ring, direction } => write!(
+        f,
+        "ring {ring} has direction {direction}, should be 1 or -1"
+      ),
+      SolveError::CurrentOutOfRange {
+        ring,
+        current,
+        positions,
+      } => write!(
+        f,
+        "ring {ring} has current value {current}, should be in 0..{positions}"
+      ),
+      SolveError::TargetOutOfRange {
+        ring,
+        target,
+        positions,
+      } => write!(
+        f,
+        "ring {ring} has target value {target}, should be in 0..{positions}"
+      ),
+      SolveError::InvalidTarget(raw) => write!(f, "failed to parse target: {raw}"),
+
+      SolveError::InvalidPuzzle(raw) => write!(f, "failed to parse puzzle: {raw}"),
+
+      SolveError::EmptyLinkages => write!(f, "at least one linkage is required"),
+      SolveError::InvalidLinkage(raw) => write!(f, "failed to parse linkage: {raw}"),
+      SolveError::LinkageWidthMismatch { linkage, rings } => {
+        write!(f, "linkage {linkage:?} doesn't match {rings} rings")
+      }
+      SolveError::DuplicateLinkage(linkage) => write!(f, "duplicate linkage: {linkage:?}"),
+      SolveError::OverrideOutOfRange { ring, width } => {
+        write!(f, "override of ring {ring}, linkage only has {width} rings")
+      }
+      SolveError::TooManyRings { rings } => {
+        write!(f, "{rings} rings, at most 64 rings are supported")
+      }
+      SolveError::TooManyLinkages { linkages, rings } => write!(
+        f,
+        "{linkages} linkages, {rings} rings only have {} distinct linkages",
+        (1u128 << rings) - 1
+      ),
+      SolveError::NoSolutionAsked => write!(f, "at least one solution should be listed"),
+      SolveError::InvalidCost(raw) => write!(f, "failed to parse cost: {raw}"),
+      SolveError::CostMismatch {
+        rotate,
+        switch,
+        linkages,
+      } => write!(
+        f,
+        "{rotate} rotate costs and {switch} switch costs don't match {linkages} linkages"
+      ),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SolveError {}
@@ -0,0 +1,123 @@
+use alloc::vec;
+use core::ops::RangeInclusive;
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+  error::SolveError, linkage::Linkage, navigation_compass::NavigationCompass,
+  puzzle::CompassPuzzle, ring::Ring,
+};
+
+/// tries of [Generator::generate_in] before giving up
+pub const ATTEMPT_LIMIT: usize = 1000;
+
+/// random puzzles that are always solvable, the same seed gives the same puzzles
+#[derive(Debug, Clone)]
+pub struct Generator {
+  rng: ChaCha8Rng,
+  rings: usize,
+  positions: u8,
+  linkages: usize,
+}
+
+/// a generated puzzle, and the least rotations to solve it
+#[derive(Debug, Clone)]
+pub struct RatedPuzzle {
+  pub puzzle: CompassPuzzle,
+  pub difficulty: usize,
+}
+
+impl Generator {
+  /// puzzles of rings with the positions each, and the number of distinct linkages
+  pub fn new(seed: u64, rings: usize, positions: u8, linkages: usize) -> Result<Self, SolveError> {
+    if rings == 0 {
+      return Err(SolveError::NoRing);
+    }
+    if rings > 64 {
+      return Err(SolveError::TooManyRings { rings });
+    }
+    Ring::new(0, 1, 1, positions).check(0)?;
+
+    if linkages == 0 {
+      return Err(SolveError::EmptyLinkages);
+    }
+    if linkages as u128 >= 1 << rings {
+      return Err(SolveError::TooManyLinkages { linkages, rings });
+    }
+
+    Ok(Self {
+      rng: ChaCha8Rng::seed_from_u64(seed),
+      rings,
+      positions,
+      linkages,
+    })
+  }
+
+  /// a random puzzle, the rings are scrambled from the target by the linkages so it's solvable,
+  /// but may happen to be already solved
+  pub fn generate(&mut self) -> Result<RatedPuzzle, SolveError> {
+    let rings = (0..self.rings)
+      .map(|_| {
+        let num = self.rng.gen_range(1..self.positions.max(2));
+        let direction = *[1, -1].choose(&mut self.rng).expect("not empty");
+        let target = self.rng.gen_range(0..self.positions) as i8;
+
+        Ring::new(target, num, direction, self.positions).with_target(target)
+      })
+      .collect_vec();
+
+    let max = u64::MAX >> (64 - self.rings);
+    let mut masks = vec![];
+    while masks.len() < self.linkages {
+      let mask = self.rng.gen_range(1..=max);
+      if !masks.contains(&mask) {
+        masks.push(mask);
+      }
+    }
+    let linkages = masks
+      .into_iter()
+      .map(|it| Linkage::new(it, self.rings as u8))
+      .collect_vec();
+
+    let solved = NavigationCompass::new(rings.clone());
+    let mut state = solved.state();
+    for linkage in &linkages {
+      let effect = solved.effect(linkage);
+      for _ in 0..self.rng.gen_range(0..solved.period(linkage)?) {
+        solved.step(&mut state, &effect);
+      }
+    }
+
+    let compass = NavigationCompass::new(
+      rings
+        .into_iter()
+        .zip(state)
+        .map(|(ring, current)| Ring { current, ..ring })
+        .collect_vec(),
+    );
+    let difficulty = compass.try_solve(linkages.clone())?.len();
+
+    Ok(RatedPuzzle {
+      puzzle: CompassPuzzle::new(compass, linkages),
+      difficulty,
+    })
+  }
+
+  /// a random puzzle with the least rotations in the range,
+  /// None if not found in [ATTEMPT_LIMIT] tries
+  pub fn generate_in(
+    &mut self,
+    difficulty: RangeInclusive<usize>,
+  ) -> Result<Option<RatedPuzzle>, SolveError> {
+    for _ in 0..ATTEMPT_LIMIT {
+      let puzzle = self.generate()?;
+      if difficulty.contains(&puzzle.difficulty) {
+        return Ok(Some(puzzle));
+      }
+    }
+
+    Ok(None)
+  }
+}
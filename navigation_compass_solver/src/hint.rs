@@ -0,0 +1,9 @@
+use crate::action::Action;
+
+/// the next action toward the target, from [crate::navigation_compass::NavigationCompass::hint]
+#[derive(Debug, Clone)]
+pub struct Hint {
+  pub action: Action,
+  /// rotations left to reach the target, including this one
+  pub remaining: usize,
+}
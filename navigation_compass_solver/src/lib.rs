@@ -1,4 +1,18 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod action;
+pub mod analysis;
+pub mod batch;
+pub mod cost;
+pub mod error;
+pub mod generator;
+pub mod hint;
 pub mod linkage;
 pub mod navigation_compass;
+pub mod puzzle;
 pub mod ring;
+pub mod step;
+#[cfg(feature = "table")]
+pub mod table;
@@ -1,26 +1,159 @@
-#[derive(Clone)]
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::SolveError, ring::Ring};
+
+/// rings rotate together in one action, bit set means linked,
+/// the highest bit is the first ring, serialized in the format of [Linkage::parse]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Linkage {
-  pub inner: bool,
-  pub middle: bool,
-  pub outer: bool,
+  pub mask: u64,
+  /// number of rings
+  pub width: u8,
+  /// linked rings moving differently from their own number and direction, by index
+  pub overrides: BTreeMap<usize, Override>,
+}
+
+/// how a linked ring moves in a linkage instead of its own number and direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Override {
+  /// the same number of positions in the opposite direction
+  Reverse,
+  /// this number of positions in the direction of the ring, negative for the opposite
+  Step(i8),
 }
 
 impl Linkage {
-  pub fn new(mask: u8) -> Self {
+  pub fn new(mask: u64, width: u8) -> Self {
     Self {
-      inner: (mask & 0b100) > 0,
-      middle: (mask & 0b010) > 0,
-      outer: (mask & 0b001) > 0,
+      mask,
+      width,
+      overrides: BTreeMap::new(),
+    }
+  }
+
+  /// link the ring at index with the override, the index must be less than the width
+  pub fn with_override(mut self, ring: usize, value: Override) -> Result<Self, SolveError> {
+    if ring >= self.width as usize {
+      return Err(SolveError::OverrideOutOfRange {
+        ring,
+        width: self.width,
+      });
+    }
+
+    self.mask |= 1 << (self.width as usize - 1 - ring);
+    self.overrides.insert(ring, value);
+    Ok(self)
+  }
+
+  /// parse from one symbol for each ring, eg. 101 for first and last ring in a 3 rings compass,
+  /// 0 is not linked, 1 is linked, - is linked in the opposite direction,
+  /// and [n] is linked moving n positions in the direction of the ring, eg. 1-0 or 1[2][-1]
+  pub fn parse(raw: &str) -> Result<Self, SolveError> {
+    let invalid = || SolveError::InvalidLinkage(raw.to_string());
+
+    let mut symbols = vec![];
+    let mut rest = raw;
+    while let Some(it) = rest.chars().next() {
+      rest = &rest[it.len_utf8()..];
+
+      let symbol = match it {
+        '0' => None,
+        '1' => Some(None),
+        '-' => Some(Some(Override::Reverse)),
+        '[' => {
+          let (step, after) = rest.split_once(']').ok_or_else(invalid)?;
+          rest = after;
+          Some(Some(Override::Step(step.parse().map_err(|_| invalid())?)))
+        }
+        _ => return Err(invalid()),
+      };
+      symbols.push(symbol);
+    }
+
+    let width = symbols.len();
+    if width == 0 || width > u64::BITS as usize {
+      return Err(invalid());
     }
+
+    let linkage = Self::new(
+      symbols
+        .iter()
+        .fold(0, |acc, it| acc << 1 | it.is_some() as u64),
+      width as u8,
+    );
+
+    symbols
+      .into_iter()
+      .enumerate()
+      .filter_map(|(idx, it)| Some((idx, it??)))
+      .try_fold(linkage, |acc, (idx, it)| acc.with_override(idx, it))
+  }
+
+  /// is ring at index linked
+  pub fn contains(&self, ring: usize) -> bool {
+    ring < self.width as usize && (self.mask >> (self.width as usize - 1 - ring)) & 1 > 0
   }
 
-  pub fn to_u8(&self) -> u8 {
-    (self.inner as u8) << 2 | (self.middle as u8) << 1 | self.outer as u8
+  /// positions the ring at index moves clockwise in one rotation, negative for anticlockwise
+  pub fn movement(&self, idx: usize, ring: &Ring) -> i16 {
+    if !self.contains(idx) {
+      return 0;
+    }
+
+    let direction = ring.direction as i16;
+    match self.overrides.get(&idx) {
+      None => direction * ring.num as i16,
+      Some(Override::Reverse) => -direction * ring.num as i16,
+      Some(Override::Step(step)) => direction * *step as i16,
+    }
+  }
+}
+
+impl core::fmt::Debug for Linkage {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for idx in 0..self.width as usize {
+      match self.overrides.get(&idx) {
+        _ if !self.contains(idx) => write!(f, "0")?,
+        None => write!(f, "1")?,
+        Some(Override::Reverse) => write!(f, "-")?,
+        Some(Override::Step(step)) => write!(f, "[{step}]")?,
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl core::fmt::Display for Linkage {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    core::fmt::Debug::fmt(self, f)
+  }
+}
+
+impl FromStr for Linkage {
+  type Err = SolveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::parse(s)
+  }
+}
+
+impl TryFrom<String> for Linkage {
+  type Error = SolveError;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    Self::parse(&value)
   }
 }
 
-impl std::fmt::Debug for Linkage {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    write!(f, "{:03b}", self.to_u8())
+impl From<Linkage> for String {
+  fn from(value: Linkage) -> Self {
+    value.to_string()
   }
 }
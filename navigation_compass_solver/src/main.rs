@@ -1,98 +1,634 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use itertools::Itertools;
 
+use navigation_compass_solver::action::Action;
+use navigation_compass_solver::batch::{NamedPuzzle, PuzzleFile};
+use navigation_compass_solver::cost::Cost;
+use navigation_compass_solver::error::SolveError;
+use navigation_compass_solver::generator::{Generator, ATTEMPT_LIMIT};
 use navigation_compass_solver::linkage::Linkage;
 use navigation_compass_solver::navigation_compass::NavigationCompass;
+use navigation_compass_solver::puzzle::CompassPuzzle;
 use navigation_compass_solver::ring::Ring;
+use navigation_compass_solver::step::Step;
+
+use report::{BatchFailed, Format, Report, EXIT_INVALID_ARGUMENTS};
+
+mod report;
+#[cfg(feature = "tui")]
+mod tui;
 
 #[derive(Debug, argh::FromArgs)]
 /// A tool for solve Navigation Compass puzzle in Honkai: Star Rail
+#[argh(
+  error_code(1, "invalid arguments"),
+  error_code(2, "invalid puzzle or other input"),
+  error_code(3, "no solution"),
+  error_code(4, "search limit exceeded"),
+  error_code(5, "failed to read or write a file"),
+  error_code(6, "some puzzles of --batch failed")
+)]
 struct Args {
-  /// inner ring current value (count from zero, clockwise)
+  /// puzzle in one line, rings in the same format as --ring separated by comma, a slash,
+  /// then linkages separated by comma, eg. 4:1:-1,0:3:-1,0:3:1/011,101,110.
+  /// --ring, --target and --linkage are ignored if given
+  #[argh(positional)]
+  puzzle: Option<String>,
+
+  /// ring info, from inner to outer, current value (count from zero, clockwise),
+  /// number per rotate, direction per rotate (clockwise: 1, anticlockwise: -1),
+  /// optional number of positions (default 6) and optional target value (default 0),
+  /// separated by colon, eg. 4:1:-1, 4:1:-1:8 or 4:1:-1:6:3.
+  /// repeat this argument for every ring, eg. -r 4:1:-1 -r 0:3:-1 -r 0:3:1.
+  #[argh(option, short = 'r')]
+  ring: Vec<String>,
+
+  /// target value of every ring, from inner to outer, separated by comma, eg. 0,3,0.
+  /// every ring should end at zero if not given.
+  #[argh(option, short = 't')]
+  target: Option<String>,
+
+  /// linkage info for a action, one digit for each ring, from inner to outer.
+  /// eg. inner and outer of three rings: 101, inner only: 100.
+  /// a linked ring can move differently in this action with "-" for the opposite direction,
+  /// or "[n]" for n positions in its direction (negative for opposite), eg. 1-0 or 10[2].
+  /// repeat this argument for multiple action, eg. -l 101 -l 001 -l 100.
+  #[argh(option, short = 'l')]
+  linkage: Vec<String>,
+
+  /// clicks for each rotation with every linkage, in the same order as the linkages,
+  /// separated by comma, or a single value for all, eg. 1,1,2.
+  /// solve for the least clicks instead of rotations if given, default 1
   #[argh(option)]
-  ic: i8,
-  /// inner ring number per rotate
-  #[argh(option, long = "in")]
-  r#in: u8,
-  /// inner ring direction per rotate clockwise: 1, anticlockwise: -1
+  cost: Option<String>,
+
+  /// clicks to switch to every linkage, same format as --cost, default 0
   #[argh(option)]
-  id: i8,
+  switch_cost: Option<String>,
+
+  /// list every solution with the least rotations, instead of only one
+  #[argh(switch)]
+  all: bool,
 
-  /// middle ring, same as above
+  /// list at most this number of solutions, at least 1, shortest first
   #[argh(option)]
-  mc: i8,
-  /// middle ring, same as above
+  top: Option<usize>,
+
+  /// print the puzzle in one line instead of solving, to share or use as the positional argument
+  #[argh(switch)]
+  notation: bool,
+
+  /// solve every puzzle in a json or toml file instead, the format is
+  /// {"puzzles": [{"name": "", "rings": [{"current": 4, "num": 1, "direction": -1}], "linkages": ["1"]}]},
+  /// optional "positions" and "target" for each ring
   #[argh(option)]
-  mn: u8,
-  /// middle ring, same as above
+  batch: Option<PathBuf>,
+
+  /// generate this number of random solvable puzzles instead of solving,
+  /// printed one per line with the least steps to solve them
   #[argh(option)]
-  md: i8,
+  generate: Option<usize>,
 
-  /// outer ring, same as above
+  /// seed of --generate, the same seed gives the same puzzles, printed if not given
   #[argh(option)]
-  oc: i8,
-  /// outer ring, same as above
+  seed: Option<u64>,
+
+  /// number of rings, positions of each ring and linkages of --generate,
+  /// separated by colon, default 3:6:3
   #[argh(option)]
-  on: u8,
-  /// outer ring, same as above
+  shape: Option<String>,
+
+  /// least steps to solve puzzles of --generate, a range or a single value,
+  /// eg. 3-8 or 5, default at least 1
   #[argh(option)]
-  od: i8,
+  difficulty: Option<String>,
 
-  /// linkage info for a action, three digits for inner, middle and outer.
-  /// eg. inner and outer: 101, inner only: 100.
-  /// repeat this argument for multiple action, eg. -l 101 -l 001 -l 100.
-  #[argh(option, short = 'l')]
-  linkage: Vec<String>,
+  /// write results of --batch, or puzzles of --generate, to a json or toml file,
+  /// decided by the extension
+  #[argh(option, short = 'o')]
+  output: Option<PathBuf>,
+
+  /// explain which states the linkages can reach instead of solving,
+  /// and which linkage is missing if unsolvable
+  #[argh(switch)]
+  explain: bool,
+
+  /// only print the next step from the current values and the steps left,
+  /// to continue after a misclick without starting over
+  #[argh(switch)]
+  hint: bool,
+
+  /// edit the puzzle in an interactive terminal ui with the solution updated live,
+  /// and play the solution step by step
+  #[cfg(feature = "tui")]
+  #[argh(switch)]
+  tui: bool,
+
+  /// output of solving: text (default), json or csv,
+  /// with the puzzle, every step with the value of every ring after it, and the error if failed.
+  /// only for solving a single puzzle, can't be used with the other modes, --trace or --dial
+  #[argh(option, default = "Format::Text")]
+  format: Format,
+
+  /// print value of every ring after each step as a table
+  #[argh(switch)]
+  trace: bool,
+
+  /// draw every ring after each step, implies --trace.
+  /// "o" is the pointer, "x" is the target
+  #[argh(switch)]
+  dial: bool,
 }
 
-fn parse_linkages(raw: Vec<String>) -> Vec<Linkage> {
-  raw
-    .into_iter()
-    .map(|it| {
-      if it.chars().count() != 3 {
-        println!("failed to parse linkage, wrong length: {}", it);
-        std::process::exit(-1);
+fn parse_rings(raw: &[String]) -> Result<Vec<Ring>, SolveError> {
+  raw.iter().map(|it| Ring::parse(it)).collect()
+}
+
+fn parse_target(raw: Option<&String>, rings: Vec<Ring>) -> Result<Vec<Ring>, SolveError> {
+  let Some(raw) = raw else {
+    return Ok(rings);
+  };
+
+  let target = raw
+    .split(',')
+    .map(i8::from_str)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|_| SolveError::InvalidTarget(raw.clone()))?;
+
+  if target.len() != rings.len() {
+    return Err(SolveError::InvalidTarget(raw.clone()));
+  }
+
+  Ok(
+    rings
+      .into_iter()
+      .zip(target)
+      .map(|(ring, target)| ring.with_target(target))
+      .collect_vec(),
+  )
+}
+
+fn parse_linkages(raw: &[String]) -> Result<Vec<Linkage>, SolveError> {
+  raw.iter().map(|it| Linkage::parse(it)).collect()
+}
+
+fn parse_input(args: &Args) -> Result<(NavigationCompass, Vec<Linkage>), SolveError> {
+  if let Some(puzzle) = &args.puzzle {
+    let puzzle = CompassPuzzle::from_str(puzzle)?;
+    return Ok((puzzle.compass, puzzle.linkages));
+  }
+
+  let navigation_compass = NavigationCompass::new(parse_target(
+    args.target.as_ref(),
+    parse_rings(&args.ring)?,
+  )?);
+  // NavigationCompass::new(vec![Ring::new(4, 1, -1, 6), Ring::new(0, 3, -1, 6), Ring::new(0, 3, 1, 6)]);
+
+  Ok((navigation_compass, parse_linkages(&args.linkage)?))
+}
+
+fn print_notation(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = parse_input(args)?;
+  navigation_compass.check(&linkages)?;
+
+  println!("{}", CompassPuzzle::new(navigation_compass, linkages));
+
+  Ok(())
+}
+
+fn parse_costs(
+  raw: Option<&String>,
+  linkages: usize,
+  default: u32,
+) -> Result<Vec<u32>, SolveError> {
+  let Some(raw) = raw else {
+    return Ok(vec![default; linkages]);
+  };
+
+  let costs = raw
+    .split(',')
+    .map(u32::from_str)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|_| SolveError::InvalidCost(raw.clone()))?;
+
+  if costs.len() == 1 {
+    Ok(vec![costs[0]; linkages])
+  } else {
+    Ok(costs)
+  }
+}
+
+/// actions to solve, and the clicks if solved with costs
+fn solve_actions(
+  args: &Args,
+  navigation_compass: &NavigationCompass,
+  linkages: Vec<Linkage>,
+) -> Result<(Vec<Action>, Option<u64>), Box<dyn Error>> {
+  if args.cost.is_some() || args.switch_cost.is_some() {
+    let cost = Cost::new(
+      parse_costs(args.cost.as_ref(), linkages.len(), 1)?,
+      parse_costs(args.switch_cost.as_ref(), linkages.len(), 0)?,
+    );
+    let (actions, clicks) = navigation_compass.try_solve_with_cost(linkages, &cost)?;
+    Ok((actions, Some(clicks)))
+  } else {
+    Ok((navigation_compass.try_solve(linkages)?, None))
+  }
+}
+
+fn solve(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = parse_input(args)?;
+  let (actions, clicks) = solve_actions(args, &navigation_compass, linkages)?;
+
+  if args.trace || args.dial {
+    print_trace(
+      &navigation_compass,
+      &navigation_compass.trace(&actions),
+      args.dial,
+    );
+  } else {
+    actions
+      .into_iter()
+      .enumerate()
+      .for_each(|(idx, val)| println!("step {}: {val:?}", idx + 1));
+  }
+
+  if let Some(clicks) = clicks {
+    println!("total clicks: {clicks}");
+  }
+
+  Ok(())
+}
+
+/// solve and print in --format, exit code by the error if failed
+fn print_report(args: &Args) -> ExitCode {
+  let report = match parse_input(args) {
+    Err(err) => Report::new(None, None).with_error(&err),
+    Ok((navigation_compass, linkages)) => {
+      let puzzle = CompassPuzzle::new(navigation_compass.clone(), linkages.clone());
+      let report = Report::new(
+        Some(puzzle.to_string()),
+        Some(
+          navigation_compass
+            .rings()
+            .iter()
+            .map(|it| it.current)
+            .collect_vec(),
+        ),
+      );
+
+      match solve_actions(args, &navigation_compass, linkages) {
+        Ok((actions, clicks)) => report.with_steps(&navigation_compass.trace(&actions), clicks),
+        Err(err) => report.with_error(err.as_ref()),
       }
+    }
+  };
+
+  match args.format {
+    Format::Json => println!(
+      "{}",
+      serde_json::to_string_pretty(&report).expect("report is serializable")
+    ),
+    _ => print!("{}", report.to_csv()),
+  }
+
+  ExitCode::from(report.exit_code())
+}
+
+fn hint(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = parse_input(args)?;
 
-      if it.chars().any(|it| it != '0' && it != '1') {
-        println!("failed to parse linkage, contain unknown character: {}", it);
-        std::process::exit(-1);
+  match navigation_compass.hint(linkages)? {
+    Some(hint) => println!("next: {:?}, {} steps left", hint.action, hint.remaining),
+    None => println!("already solved"),
+  }
+
+  Ok(())
+}
+
+fn list_solutions(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = parse_input(args)?;
+
+  let solutions = match args.top {
+    Some(top) => navigation_compass.shortest_solutions(linkages, top)?,
+    None => navigation_compass.all_minimal_solutions(linkages)?,
+  };
+
+  for (idx, solution) in solutions.iter().enumerate() {
+    let counts = solution
+      .iter()
+      .map(|it| format!("{it:?}"))
+      .dedup_with_count()
+      .map(|(count, linkage)| format!("{linkage} x{count}"))
+      .join(", ");
+    if solution.is_empty() {
+      println!("solution {}: already solved", idx + 1);
+    } else {
+      println!("solution {} ({} steps): {counts}", idx + 1, solution.len());
+    }
+  }
+
+  Ok(())
+}
+
+fn batch(path: &Path, output: Option<&PathBuf>) -> Result<(), Box<dyn Error>> {
+  let content = std::fs::read_to_string(path)?;
+  let puzzles: PuzzleFile = if is_toml(path) {
+    toml::from_str(&content)?
+  } else {
+    serde_json::from_str(&content)?
+  };
+
+  let results = puzzles.solve_all();
+
+  for result in &results.results {
+    match (&result.solution, &result.error) {
+      (Some(solution), _) => println!("{}: {} steps", result.name, solution.len()),
+      (_, Some(error)) => println!("{}: {error}", result.name),
+      _ => {}
+    }
+  }
+
+  if let Some(output) = output {
+    let content = if is_toml(output) {
+      toml::to_string_pretty(&results)?
+    } else {
+      serde_json::to_string_pretty(&results)?
+    };
+    std::fs::write(output, content)?;
+  }
+
+  let failed = results
+    .results
+    .iter()
+    .filter(|it| it.error.is_some())
+    .count();
+  if failed > 0 {
+    return Err(
+      BatchFailed {
+        failed,
+        total: results.results.len(),
+      }
+      .into(),
+    );
+  }
+
+  Ok(())
+}
+
+fn generate(args: &Args, count: usize) -> Result<(), Box<dyn Error>> {
+  let seed = args.seed.unwrap_or_else(|| {
+    let seed = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |it| it.as_nanos() as u64);
+    eprintln!("seed: {seed}");
+    seed
+  });
+
+  let shape = args.shape.as_deref().unwrap_or("3:6:3");
+  let Some((rings, positions, linkages)) =
+    shape
+      .split(':')
+      .collect_tuple()
+      .and_then(|(rings, positions, linkages)| {
+        Some((
+          rings.parse().ok()?,
+          positions.parse().ok()?,
+          linkages.parse().ok()?,
+        ))
+      })
+  else {
+    return Err(format!("failed to parse shape: {shape}").into());
+  };
+
+  let difficulty = match args.difficulty.as_deref() {
+    None => 1..=usize::MAX,
+    Some(raw) => {
+      let parsed = match raw.split_once('-') {
+        Some((min, max)) => min.parse().and_then(|min| Ok(min..=max.parse()?)),
+        None => raw.parse().map(|it| it..=it),
+      };
+      parsed.map_err(|_| format!("failed to parse difficulty: {raw}"))?
+    }
+  };
+
+  let mut generator = Generator::new(seed, rings, positions, linkages)?;
+  let mut puzzles = vec![];
+  for idx in 0..count {
+    let Some(rated) = generator.generate_in(difficulty.clone())? else {
+      return Err(
+        format!(
+          "no puzzle takes {} to {} steps in {ATTEMPT_LIMIT} tries",
+          difficulty.start(),
+          difficulty.end()
+        )
+        .into(),
+      );
+    };
+
+    println!("{} {}", rated.puzzle, rated.difficulty);
+    puzzles.push(NamedPuzzle {
+      name: format!("{seed}-{}", idx + 1),
+      puzzle: rated.puzzle,
+    });
+  }
+
+  if let Some(output) = &args.output {
+    let puzzles = PuzzleFile { puzzles };
+    let content = if is_toml(output) {
+      toml::to_string_pretty(&puzzles)?
+    } else {
+      serde_json::to_string_pretty(&puzzles)?
+    };
+    std::fs::write(output, content)?;
+  }
+
+  Ok(())
+}
+
+fn is_toml(path: &Path) -> bool {
+  path.extension().is_some_and(|it| it == "toml")
+}
+
+fn print_trace(navigation_compass: &NavigationCompass, steps: &[Step], dial: bool) {
+  let rings = navigation_compass.rings();
+  let linkage_width = steps
+    .iter()
+    .map(|it| format!("{:?}", it.action).len())
+    .fold("linkage".len(), usize::max);
+
+  let header = (0..rings.len()).fold(
+    format!("step  {:<linkage_width$}", "linkage"),
+    |acc, idx| acc + &format!("  ring {idx:<2}"),
+  );
+  println!("{}", header.trim_end());
+
+  let print_row = |step: usize, linkage: String, values: &[i8]| {
+    let row = values.iter().fold(
+      format!("{step:>4}  {linkage:<linkage_width$}"),
+      |acc, it| acc + &format!("  {it:<7}"),
+    );
+    println!("{}", row.trim_end());
+
+    if dial {
+      for (idx, (ring, value)) in rings.iter().zip(values).enumerate() {
+        indent(6);
+        println!("ring {idx:<2} {}", draw_dial(ring, *value));
       }
+    }
+  };
 
-      let linkage =
-        it.chars().rev().enumerate().fold(
-          0,
-          |acc, (idx, val)| {
-            if val == '1' {
-              acc | (1 << idx)
-            } else {
-              acc
-            }
-          },
-        );
-
-      Linkage::new(linkage)
+  print_row(
+    0,
+    String::new(),
+    &rings.iter().map(|it| it.current).collect_vec(),
+  );
+  for (idx, step) in steps.iter().enumerate() {
+    print_row(idx + 1, format!("{:?}", step.action), &step.rings);
+  }
+}
+
+/// positions from zero, clockwise
+fn draw_dial(ring: &Ring, value: i8) -> String {
+  (0..ring.positions)
+    .map(|it| it as i8)
+    .map(|it| {
+      if it == value {
+        'o'
+      } else if it == ring.target {
+        'x'
+      } else {
+        '.'
+      }
     })
-    .collect_vec()
+    .join(" ")
+}
+
+fn explain(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = parse_input(args)?;
+  let analysis = navigation_compass.analyze(&linkages)?;
+
+  println!("reachable states: {}", analysis.reachable.len());
+  if analysis.reachable.len() <= 64 {
+    for state in &analysis.reachable {
+      indent(2);
+      println!("{}", state.iter().join(","));
+    }
+  }
+
+  for (idx, values) in analysis.unreachable_values.iter().enumerate() {
+    if values.is_empty() {
+      println!("ring {idx}: every value is reachable");
+    } else {
+      println!("ring {idx}: never reach {}", values.iter().join(", "));
+    }
+  }
+
+  let target = navigation_compass
+    .rings()
+    .iter()
+    .map(|it| it.target)
+    .join(",");
+  if analysis.solvable {
+    println!("target {target} is reachable");
+  } else {
+    println!("target {target} is not reachable");
+    match analysis.suggestion {
+      Some(linkage) => println!("add linkage {linkage:?} to make it solvable"),
+      None => println!("no single linkage can make it solvable"),
+    }
+  }
+
+  Ok(())
 }
 
-fn main() {
+/// starts with the example puzzle if none is given
+#[cfg(feature = "tui")]
+fn run_tui(args: &Args) -> Result<(), Box<dyn Error>> {
+  let (navigation_compass, linkages) = if args.puzzle.is_none() && args.ring.is_empty() {
+    let puzzle = CompassPuzzle::from_str("4:1:-1,0:3:-1,0:3:1/011,101,110")?;
+    (puzzle.compass, puzzle.linkages)
+  } else {
+    parse_input(args)?
+  };
+  navigation_compass.check(&linkages)?;
+
+  tui::run(navigation_compass, linkages)
+}
+
+fn indent(num: usize) {
+  print!("{0:>1$}", "", num);
+}
+
+/// the first argument given that --format doesn't apply to
+fn unformatted_mode(args: &Args) -> Option<&'static str> {
+  #[cfg(feature = "tui")]
+  if args.tui {
+    return Some("--tui");
+  }
+
+  [
+    (args.batch.is_some(), "--batch"),
+    (args.generate.is_some(), "--generate"),
+    (args.notation, "--notation"),
+    (args.explain, "--explain"),
+    (args.hint, "--hint"),
+    (args.all, "--all"),
+    (args.top.is_some(), "--top"),
+    (args.trace, "--trace"),
+    (args.dial, "--dial"),
+  ]
+  .into_iter()
+  .find_map(|(given, name)| given.then_some(name))
+}
+
+fn main() -> ExitCode {
   let args: Args = argh::from_env();
 
-  let navigation_compass = NavigationCompass::new(
-    Ring::new(args.ic, args.r#in, args.id),
-    Ring::new(args.mc, args.mn, args.md),
-    Ring::new(args.oc, args.on, args.od),
-  );
-  // NavigationCompass::new(Ring::new(4, 1, -1), Ring::new(0, 3, -1), Ring::new(0, 3, 1));
+  if args.format != Format::Text {
+    if let Some(mode) = unformatted_mode(&args) {
+      eprintln!("--format can't be used with {mode}, only for solving a single puzzle");
+      return ExitCode::from(EXIT_INVALID_ARGUMENTS);
+    }
+  }
 
-  let result = navigation_compass.try_solve(parse_linkages(args.linkage));
+  #[cfg(feature = "tui")]
+  if args.tui {
+    return finish(run_tui(&args));
+  }
 
-  if let Some(result) = result {
-    result
-      .into_iter()
-      .enumerate()
-      .for_each(|(idx, val)| println!("step {}: {val:?}", idx + 1))
+  let result = if let Some(path) = &args.batch {
+    batch(path, args.output.as_ref())
+  } else if let Some(count) = args.generate {
+    generate(&args, count)
+  } else if args.notation {
+    print_notation(&args)
+  } else if args.explain {
+    explain(&args)
+  } else if args.hint {
+    hint(&args)
+  } else if args.all || args.top.is_some() {
+    list_solutions(&args)
+  } else if args.format != Format::Text {
+    return print_report(&args);
   } else {
-    println!("failed to solve in 100 step");
+    solve(&args)
+  };
+
+  finish(result)
+}
+
+fn finish(result: Result<(), Box<dyn Error>>) -> ExitCode {
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("{err}");
+      ExitCode::from(report::exit_code(err.as_ref()))
+    }
   }
 }
@@ -1,79 +1,477 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use crate::{action::Action, linkage::Linkage, ring::Ring};
+use crate::{
+  action::Action,
+  analysis::Analysis,
+  cost::Cost,
+  error::SolveError,
+  hint::Hint,
+  linkage::Linkage,
+  ring::{gcd, Ring},
+  step::Step,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationCompass {
-  inner: Ring,
-  middle: Ring,
-  outer: Ring,
+  rings: Vec<Ring>,
 }
 
-macro_rules! gen_rotate {
-  ($fn_name:ident, $part:ident) => {
-    fn $fn_name(&mut self) {
-      self.$part.rotate();
-    }
-  };
+/// rings separated by comma, in the format of [Ring::parse], eg. 4:1:-1,0:3:-1,0:3:1
+impl core::fmt::Display for NavigationCompass {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}", self.rings.iter().join(","))
+  }
+}
+
+impl FromStr for NavigationCompass {
+  type Err = SolveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self::new(s.split(',').map(Ring::parse).try_collect()?))
+  }
 }
 
+/// current value of every ring
+type State = Vec<i8>;
+
+/// (cost, rotations) and rotate count of each linkage to reach a state
+type Reached = ((u64, u32), Vec<u32>);
+
+/// rotations and rotate count of each linkage to reach a state, sorted by rotations
+type Ranked = Vec<(u32, Vec<u32>)>;
+
+/// maximum number of states tried for a linkage while solving
+pub const SEARCH_LIMIT: usize = 1 << 20;
+
+/// maximum number of rings to look for a suggested linkage, every possible linkage is tried
+const SUGGESTION_WIDTH_LIMIT: u8 = 16;
+
 impl NavigationCompass {
-  pub fn new(inner: Ring, middle: Ring, outer: Ring) -> Self {
-    Self {
-      inner,
-      middle,
-      outer,
+  /// rings from inner to outer
+  pub fn new(rings: Vec<Ring>) -> Self {
+    Self { rings }
+  }
+
+  pub fn rings(&self) -> &[Ring] {
+    &self.rings
+  }
+
+  pub fn is_solved(&self) -> bool {
+    self.rings.iter().all(Ring::is_solved)
+  }
+
+  /// find the solution with the least rotations to get every ring to its target
+  pub fn try_solve(&self, available_linkages: Vec<Linkage>) -> Result<Vec<Action>, SolveError> {
+    self.check(&available_linkages)?;
+
+    let counts = self.solve_counts(&available_linkages, |_, count| count as u64)?;
+
+    Ok(Self::to_actions(available_linkages, counts))
+  }
+
+  /// find the solution with the least clicks, and the clicks it takes,
+  /// actions with the same linkage are grouped together in the order of the linkages
+  pub fn try_solve_with_cost(
+    &self,
+    available_linkages: Vec<Linkage>,
+    cost: &Cost,
+  ) -> Result<(Vec<Action>, u64), SolveError> {
+    self.check(&available_linkages)?;
+    cost.check(available_linkages.len())?;
+
+    let counts = self.solve_counts(&available_linkages, |idx, count| cost.of(idx, count))?;
+    let total = counts
+      .iter()
+      .enumerate()
+      .map(|(idx, count)| cost.of(idx, *count))
+      .sum();
+
+    Ok((Self::to_actions(available_linkages, counts), total))
+  }
+
+  /// every solution with the least rotations, each is a distinct set of rotate counts,
+  /// actions with the same linkage are grouped together in the order of the linkages
+  pub fn all_minimal_solutions(
+    &self,
+    available_linkages: Vec<Linkage>,
+  ) -> Result<Vec<Vec<Action>>, SolveError> {
+    self.ranked_solutions(available_linkages, None)
+  }
+
+  /// at most n solutions with the least rotations, shortest first,
+  /// each is a distinct set of rotate counts, n must be at least 1
+  pub fn shortest_solutions(
+    &self,
+    available_linkages: Vec<Linkage>,
+    n: usize,
+  ) -> Result<Vec<Vec<Action>>, SolveError> {
+    if n == 0 {
+      return Err(SolveError::NoSolutionAsked);
     }
+
+    self.ranked_solutions(available_linkages, Some(n))
   }
 
-  pub fn try_solve(&self, available_linkages: Vec<Linkage>) -> Option<Vec<Action>> {
-    for combination_num in 1..=100 {
-      let result = available_linkages
-        .iter()
-        .cloned()
-        .combinations_with_replacement(combination_num)
-        .map(|it| it.into_iter().map(Action::Rotate).collect_vec())
-        .find(|it| self.do_actions(it).is_solved());
+  fn ranked_solutions(
+    &self,
+    available_linkages: Vec<Linkage>,
+    keep: Option<usize>,
+  ) -> Result<Vec<Vec<Action>>, SolveError> {
+    self.check(&available_linkages)?;
+
+    let solutions = self
+      .reachable_ranked(&available_linkages, keep)?
+      .remove(&self.target_state())
+      .ok_or(SolveError::Unsolvable)?;
+
+    Ok(
+      solutions
+        .into_iter()
+        .map(|(_, counts)| Self::to_actions(available_linkages.clone(), counts))
+        .collect_vec(),
+    )
+  }
+
+  /// cheapest rotate counts of each linkage to get to the target
+  fn solve_counts(
+    &self,
+    linkages: &[Linkage],
+    cost: impl Fn(usize, u32) -> u64,
+  ) -> Result<Vec<u32>, SolveError> {
+    let (_, counts) = self
+      .reachable_by(linkages, cost)?
+      .remove(&self.target_state())
+      .ok_or(SolveError::Unsolvable)?;
+
+    Ok(counts)
+  }
+
+  fn to_actions(linkages: Vec<Linkage>, counts: Vec<u32>) -> Vec<Action> {
+    linkages
+      .into_iter()
+      .zip(counts)
+      .flat_map(|(linkage, count)| core::iter::repeat_n(linkage, count as usize))
+      .map(Action::Rotate)
+      .collect_vec()
+  }
+
+  /// only the next action of a solution with the least rotations, None if already solved
+  pub fn hint(&self, available_linkages: Vec<Linkage>) -> Result<Option<Hint>, SolveError> {
+    let actions = self.try_solve(available_linkages)?;
+    let remaining = actions.len();
+
+    Ok(
+      actions
+        .into_iter()
+        .next()
+        .map(|action| Hint { action, remaining }),
+    )
+  }
+
+  /// same as [Self::try_solve], with the value of every ring after each step
+  pub fn try_solve_trace(&self, available_linkages: Vec<Linkage>) -> Result<Vec<Step>, SolveError> {
+    Ok(self.trace(&self.try_solve(available_linkages)?))
+  }
+
+  /// value of every ring after each action
+  pub fn trace(&self, actions: &[Action]) -> Vec<Step> {
+    let mut compass = self.clone();
+
+    actions
+      .iter()
+      .map(|action| {
+        compass.apply(action);
+        Step {
+          action: action.clone(),
+          rings: compass.state(),
+        }
+      })
+      .collect_vec()
+  }
+
+  /// do one action, eg. to play a solution step by step
+  pub fn apply(&mut self, action: &Action) {
+    match action {
+      Action::Rotate(linkage) => self.rotate(linkage),
+    }
+  }
+
+  /// explain what the linkages can do, and what's missing if the puzzle is unsolvable
+  pub fn analyze(&self, available_linkages: &[Linkage]) -> Result<Analysis, SolveError> {
+    self.check(available_linkages)?;
+
+    let (reachable, rotations): (Vec<_>, Vec<_>) = self
+      .reachable(available_linkages)?
+      .into_iter()
+      .map(|(state, ((_, rotations), _))| (state, rotations))
+      .unzip();
+    let target = self.target_state();
+    let solvable = reachable.contains(&target);
+
+    let unreachable_values = self
+      .rings
+      .iter()
+      .enumerate()
+      .map(|(idx, ring)| {
+        (0..ring.positions)
+          .map(|it| it as i8)
+          .filter(|value| reachable.iter().all(|it| it[idx] != *value))
+          .collect_vec()
+      })
+      .collect_vec();
+
+    let suggestion = if solvable || self.rings.len() > SUGGESTION_WIDTH_LIMIT as usize {
+      None
+    } else {
+      self.suggest(available_linkages, &reachable)
+    };
 
-      if let Some(result) = result {
-        return Some(result);
+    Ok(Analysis {
+      reachable,
+      rotations,
+      solvable,
+      unreachable_values,
+      suggestion,
+    })
+  }
+
+  /// the linkage with the fewest rings that makes the target reachable, from the reachable states
+  /// in order, the target is reachable with one more linkage if rotating back from the target with
+  /// it gets to a reachable state, gives up after [SEARCH_LIMIT] states over every linkage
+  fn suggest(&self, available_linkages: &[Linkage], reachable: &[State]) -> Option<Linkage> {
+    let width = self.rings.len() as u8;
+    let mut budget = SEARCH_LIMIT;
+
+    let linkages = (1..=u64::MAX >> (u64::BITS - width as u32))
+      .map(|mask| Linkage::new(mask, width))
+      .filter(|it| !available_linkages.contains(it))
+      .sorted_by_key(|it| it.mask.count_ones());
+
+    for linkage in linkages {
+      let period = self.period(&linkage).ok()?;
+      budget = budget.checked_sub(period as usize)?;
+
+      let back = self
+        .effect(&linkage)
+        .iter()
+        .zip(&self.rings)
+        .map(|(effect, ring)| (ring.positions as i16 - effect) % ring.positions as i16)
+        .collect_vec();
+      let mut state = self.target_state();
+      for _ in 0..period {
+        if reachable.binary_search(&state).is_ok() {
+          return Some(linkage);
+        }
+        self.step(&mut state, &back);
       }
     }
 
     None
   }
 
-  fn is_solved(&self) -> bool {
-    self.inner.current == 0 && self.middle.current == 0 && self.outer.current == 0
+  /// every reachable state, with the least rotate counts for each linkage to get there
+  fn reachable(&self, linkages: &[Linkage]) -> Result<BTreeMap<State, Reached>, SolveError> {
+    self.reachable_by(linkages, |_, count| count as u64)
   }
 
-  fn do_actions(&self, actions: &Vec<Action>) -> NavigationCompass {
-    let mut result = self.clone();
+  /// every reachable state, with the cheapest rotate counts for each linkage to get there,
+  /// cost is given by the index of linkage and the rotate count, ties are broken by fewer rotations
+  ///
+  /// rotations commute, so a state is only decided by how many times each linkage is used,
+  /// and using a linkage for a whole [Self::period] changes nothing, this walks through the
+  /// linkages one by one, keeping the cheapest rotate counts for every reachable state
+  fn reachable_by(
+    &self,
+    linkages: &[Linkage],
+    cost: impl Fn(usize, u32) -> u64,
+  ) -> Result<BTreeMap<State, Reached>, SolveError> {
+    let mut reachable = BTreeMap::<State, Reached>::new();
+    reachable.insert(self.state(), ((0, 0), vec![]));
 
-    for action in actions {
-      match action {
-        Action::Rotate(Linkage {
-          inner,
-          middle,
-          outer,
-        }) => {
-          if *inner {
-            result.rotate_inner();
+    for (idx, linkage) in linkages.iter().enumerate() {
+      let effect = self.effect(linkage);
+      let period = self.period(linkage)?;
+      Self::check_search(reachable.len(), period)?;
+      let mut next = BTreeMap::<State, Reached>::new();
+
+      for (mut state, ((total, rotations), counts)) in reachable {
+        for count in 0..period {
+          let total = (total + cost(idx, count), rotations + count);
+
+          let better = next.get(&state).is_none_or(|(it, _)| *it > total);
+
+          if better {
+            let mut counts = counts.clone();
+            counts.push(count);
+            next.insert(state.clone(), (total, counts));
           }
-          if *middle {
-            result.rotate_middle();
+
+          self.step(&mut state, &effect);
+        }
+      }
+
+      reachable = next;
+    }
+
+    Ok(reachable)
+  }
+
+  /// every reachable state, with the rotate counts for each linkage to get there,
+  /// sorted by rotations, keeping the best `keep` for each state,
+  /// or every one with the least rotations if None
+  ///
+  /// same as [Self::reachable_by], rotate counts not kept for the state they reach
+  /// can't be part of a kept one later, as there are enough better ones sharing the same suffix
+  fn reachable_ranked(
+    &self,
+    linkages: &[Linkage],
+    keep: Option<usize>,
+  ) -> Result<BTreeMap<State, Ranked>, SolveError> {
+    let mut reachable = BTreeMap::<State, Vec<(u32, Vec<u32>)>>::new();
+    reachable.insert(self.state(), vec![(0, vec![])]);
+
+    for linkage in linkages {
+      let effect = self.effect(linkage);
+      let period = self.period(linkage)?;
+      Self::check_search(reachable.values().map(Vec::len).sum(), period)?;
+      let mut next = BTreeMap::<State, Vec<(u32, Vec<u32>)>>::new();
+
+      for (mut state, ranked) in reachable {
+        for count in 0..period {
+          let kept = next.entry(state.clone()).or_default();
+
+          for (total, counts) in &ranked {
+            let total = total + count;
+            let mut counts = counts.clone();
+            counts.push(count);
+
+            match keep {
+              Some(_) => kept.push((total, counts)),
+              None => {
+                if kept.first().is_none_or(|(it, _)| *it > total) {
+                  kept.clear();
+                }
+                if kept.first().is_none_or(|(it, _)| *it == total) {
+                  kept.push((total, counts));
+                }
+              }
+            }
           }
-          if *outer {
-            result.rotate_outer();
+
+          if let Some(keep) = keep {
+            kept.sort();
+            kept.truncate(keep);
           }
+
+          self.step(&mut state, &effect);
         }
       }
+
+      reachable = next;
     }
 
-    result
+    reachable.values_mut().for_each(|it| it.sort());
+
+    Ok(reachable)
   }
 
-  gen_rotate!(rotate_inner, inner);
-  gen_rotate!(rotate_middle, middle);
-  gen_rotate!(rotate_outer, outer);
+  /// every ring is valid, and linkages are not empty, unique and match the rings
+  pub fn check(&self, linkages: &[Linkage]) -> Result<(), SolveError> {
+    if self.rings.is_empty() {
+      return Err(SolveError::NoRing);
+    }
+    for (idx, ring) in self.rings.iter().enumerate() {
+      ring.check(idx)?;
+    }
+
+    if linkages.is_empty() {
+      return Err(SolveError::EmptyLinkages);
+    }
+    for (idx, linkage) in linkages.iter().enumerate() {
+      if linkage.width as usize != self.rings.len() {
+        return Err(SolveError::LinkageWidthMismatch {
+          linkage: linkage.clone(),
+          rings: self.rings.len(),
+        });
+      }
+      if linkages[..idx].contains(linkage) {
+        return Err(SolveError::DuplicateLinkage(linkage.clone()));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// number of rotations with the linkage to get every ring back to the same value,
+  /// no more than [SEARCH_LIMIT] as every rotation makes a state to keep
+  pub fn period(&self, linkage: &Linkage) -> Result<u32, SolveError> {
+    self
+      .rings
+      .iter()
+      .zip(self.effect(linkage))
+      .try_fold(1u32, |acc, (ring, effect)| {
+        let positions = ring.positions as u32;
+        let period = positions / gcd(positions, effect as u32);
+        (acc / gcd(acc, period))
+          .checked_mul(period)
+          .filter(|it| *it as usize <= SEARCH_LIMIT)
+          .ok_or(SolveError::SearchLimitExceeded {
+            limit: SEARCH_LIMIT,
+          })
+      })
+  }
+
+  /// the states times the period of the next linkage are tried, fail before trying them
+  fn check_search(states: usize, period: u32) -> Result<(), SolveError> {
+    match states.checked_mul(period as usize) {
+      Some(it) if it <= SEARCH_LIMIT => Ok(()),
+      _ => Err(SolveError::SearchLimitExceeded {
+        limit: SEARCH_LIMIT,
+      }),
+    }
+  }
+
+  /// value added to each ring by one rotation with the linkage, in 0..positions
+  pub(crate) fn effect(&self, linkage: &Linkage) -> Vec<i16> {
+    self
+      .rings
+      .iter()
+      .enumerate()
+      .map(|(idx, ring)| {
+        linkage
+          .movement(idx, ring)
+          .rem_euclid(ring.positions as i16)
+      })
+      .collect_vec()
+  }
+
+  /// rotate a state by effect from [Self::effect]
+  pub(crate) fn step(&self, state: &mut State, effect: &[i16]) {
+    for ((value, ring), effect) in state.iter_mut().zip(&self.rings).zip(effect) {
+      *value = ((*value as i16 + effect) % ring.positions as i16) as i8;
+    }
+  }
+
+  pub(crate) fn state(&self) -> State {
+    self.rings.iter().map(|it| it.current).collect_vec()
+  }
+
+  pub(crate) fn target_state(&self) -> State {
+    self.rings.iter().map(|it| it.target).collect_vec()
+  }
+
+  fn rotate(&mut self, linkage: &Linkage) {
+    let mut state = self.state();
+    self.step(&mut state, &self.effect(linkage));
+
+    for (ring, current) in self.rings.iter_mut().zip(state) {
+      ring.current = current;
+    }
+  }
 }
@@ -0,0 +1,75 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use itertools::Itertools;
+use puzzle_search::puzzle::Puzzle;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::SolveError, linkage::Linkage, navigation_compass::NavigationCompass};
+
+/// a compass with the linkages available, to share a puzzle in one line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompassPuzzle {
+  #[serde(flatten)]
+  pub compass: NavigationCompass,
+  pub linkages: Vec<Linkage>,
+}
+
+impl CompassPuzzle {
+  pub fn new(compass: NavigationCompass, linkages: Vec<Linkage>) -> Self {
+    Self { compass, linkages }
+  }
+}
+
+/// compass and linkages separated by slash, linkages are separated by comma,
+/// eg. 4:1:-1,0:3:-1,0:3:1/011,101,110
+impl core::fmt::Display for CompassPuzzle {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}/{}", self.compass, self.linkages.iter().join(","))
+  }
+}
+
+impl FromStr for CompassPuzzle {
+  type Err = SolveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let Some((compass, linkages)) = s.trim().split_once('/') else {
+      return Err(SolveError::InvalidPuzzle(s.to_string()));
+    };
+
+    Ok(Self::new(
+      compass.parse()?,
+      linkages.split(',').map(Linkage::parse).try_collect()?,
+    ))
+  }
+}
+
+/// one rotation of a linkage per action
+impl Puzzle for CompassPuzzle {
+  type State = Vec<i8>;
+  type Action = Linkage;
+
+  fn initial(&self) -> Self::State {
+    self.compass.state()
+  }
+
+  fn actions(&self, _state: &Self::State) -> Vec<Self::Action> {
+    self.linkages.clone()
+  }
+
+  fn apply(&self, state: &Self::State, action: &Self::Action) -> Self::State {
+    let mut state = state.clone();
+    self.compass.step(&mut state, &self.compass.effect(action));
+    state
+  }
+
+  fn is_goal(&self, state: &Self::State) -> bool {
+    *state == self.compass.target_state()
+  }
+
+  /// an unsolved state needs at least one more rotation
+  fn heuristic(&self, state: &Self::State) -> u64 {
+    u64::from(!self.is_goal(state))
+  }
+}
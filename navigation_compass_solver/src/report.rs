@@ -0,0 +1,204 @@
+//! output of solving for scripts and bots, see --format,
+//! and the exit code for every kind of error
+
+use std::error::Error;
+use std::str::FromStr;
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use navigation_compass_solver::error::SolveError;
+use navigation_compass_solver::step::Step;
+
+pub const EXIT_INVALID_ARGUMENTS: u8 = 1;
+pub const EXIT_INVALID_INPUT: u8 = 2;
+pub const EXIT_UNSOLVABLE: u8 = 3;
+pub const EXIT_SEARCH_LIMIT: u8 = 4;
+pub const EXIT_IO: u8 = 5;
+pub const EXIT_BATCH_FAILED: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Text,
+  Json,
+  Csv,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(Format::Text),
+      "json" => Ok(Format::Json),
+      "csv" => Ok(Format::Csv),
+      _ => Err(format!("unknown format {s}, should be text, json or csv")),
+    }
+  }
+}
+
+/// some puzzles of a batch have no solution or can't be solved
+#[derive(Debug, Clone)]
+pub struct BatchFailed {
+  pub failed: usize,
+  pub total: usize,
+}
+
+impl std::fmt::Display for BatchFailed {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} of {} puzzles failed", self.failed, self.total)
+  }
+}
+
+impl Error for BatchFailed {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+  /// puzzle in one line, None if the input can't be parsed
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub puzzle: Option<String>,
+  /// value of every ring before the first step
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub initial: Option<Vec<i8>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub steps: Option<Vec<ReportStep>>,
+  /// total clicks when solved with costs
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub clicks: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<ReportError>,
+}
+
+/// linkage of a step, and the value of every ring after it
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportStep {
+  pub linkage: String,
+  pub rings: Vec<i8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportError {
+  pub kind: &'static str,
+  /// exit code of the process
+  pub code: u8,
+  pub message: String,
+}
+
+impl ReportError {
+  pub fn new(err: &(dyn Error + 'static)) -> Self {
+    let code = exit_code(err);
+    let kind = match code {
+      EXIT_UNSOLVABLE => "unsolvable",
+      EXIT_SEARCH_LIMIT => "search_limit_exceeded",
+      EXIT_IO => "io",
+      EXIT_BATCH_FAILED => "batch_failed",
+      _ => "invalid_input",
+    };
+
+    Self {
+      kind,
+      code,
+      message: err.to_string(),
+    }
+  }
+}
+
+impl Report {
+  pub fn new(puzzle: Option<String>, initial: Option<Vec<i8>>) -> Self {
+    Self {
+      puzzle,
+      initial,
+      steps: None,
+      clicks: None,
+      error: None,
+    }
+  }
+
+  pub fn with_steps(mut self, steps: &[Step], clicks: Option<u64>) -> Self {
+    self.steps = Some(
+      steps
+        .iter()
+        .map(|it| ReportStep {
+          linkage: format!("{:?}", it.action),
+          rings: it.rings.clone(),
+        })
+        .collect_vec(),
+    );
+    self.clicks = clicks;
+    self
+  }
+
+  pub fn with_error(mut self, err: &(dyn Error + 'static)) -> Self {
+    self.error = Some(ReportError::new(err));
+    self
+  }
+
+  pub fn exit_code(&self) -> u8 {
+    self.error.as_ref().map_or(0, |it| it.code)
+  }
+
+  /// one row for each step, with the initial state as step 0,
+  /// and the total clicks in every row when solved with costs,
+  /// or a row of the error
+  pub fn to_csv(&self) -> String {
+    let puzzle = quote(self.puzzle.as_deref().unwrap_or_default());
+
+    if let Some(error) = &self.error {
+      return format!(
+        "puzzle,error,code,message\n{puzzle},{},{},{}\n",
+        error.kind,
+        error.code,
+        quote(&error.message)
+      );
+    }
+
+    let initial = self.initial.clone().unwrap_or_default();
+    let (clicks_header, clicks) = match self.clicks {
+      Some(clicks) => (",clicks".to_string(), format!(",{clicks}")),
+      None => (String::new(), String::new()),
+    };
+    let mut csv = format!(
+      "puzzle,step,linkage,{}{clicks_header}\n",
+      (0..initial.len()).map(|it| format!("ring {it}")).join(",")
+    );
+    csv += &format!("{puzzle},0,,{}{clicks}\n", initial.iter().join(","));
+    for (idx, step) in self.steps.iter().flatten().enumerate() {
+      csv += &format!(
+        "{puzzle},{},{},{}{clicks}\n",
+        idx + 1,
+        step.linkage,
+        step.rings.iter().join(",")
+      );
+    }
+
+    csv
+  }
+}
+
+/// quoted if it has comma, quote or line break
+fn quote(value: &str) -> String {
+  if value.contains([',', '"', '\n']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+/// exit code of the error, 1 is left for invalid arguments
+pub fn exit_code(err: &(dyn Error + 'static)) -> u8 {
+  if let Some(err) = err.downcast_ref::<SolveError>() {
+    return match err {
+      SolveError::Unsolvable => EXIT_UNSOLVABLE,
+      SolveError::SearchLimitExceeded { .. } => EXIT_SEARCH_LIMIT,
+      _ => EXIT_INVALID_INPUT,
+    };
+  }
+
+  if err.is::<BatchFailed>() {
+    EXIT_BATCH_FAILED
+  } else if err.is::<std::io::Error>() {
+    EXIT_IO
+  } else {
+    EXIT_INVALID_INPUT
+  }
+}
@@ -1,30 +1,144 @@
-#[derive(Clone)]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Ring {
   pub current: i8,
   pub num: u8,
   pub direction: i8,
+  /// number of positions on the ring, current value is counted in 0..positions
+  #[serde(default = "default_positions")]
+  pub positions: u8,
+  /// value the ring should end at
+  #[serde(default)]
+  pub target: i8,
+}
+
+fn default_positions() -> u8 {
+  6
 }
 
-impl std::fmt::Debug for Ring {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Ring {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     write!(f, "{}", self.current)
   }
 }
 
+/// same format as [Ring::parse], optional values are omitted when they are the default
+impl core::fmt::Display for Ring {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}:{}:{}", self.current, self.num, self.direction)?;
+    if self.positions != default_positions() || self.target != 0 {
+      write!(f, ":{}", self.positions)?;
+    }
+    if self.target != 0 {
+      write!(f, ":{}", self.target)?;
+    }
+    Ok(())
+  }
+}
+
+impl FromStr for Ring {
+  type Err = SolveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::parse(s)
+  }
+}
+
 impl Ring {
-  pub fn new(current: i8, num: u8, direction: i8) -> Self {
+  pub fn new(current: i8, num: u8, direction: i8, positions: u8) -> Self {
     Self {
       current,
       num,
       direction,
+      positions,
+      target: 0,
     }
   }
 
-  pub fn rotate(&mut self) {
-    self.current += self.direction * self.num as i8;
-    self.current %= 6;
-    if self.current < 0 {
-      self.current += 6;
+  /// parse from current value, number per rotate, direction per rotate, optional number
+  /// of positions (default 6) and optional target value (default 0), separated by colon,
+  /// eg. 4:1:-1, 4:1:-1:8 or 4:1:-1:6:3
+  pub fn parse(raw: &str) -> Result<Self, SolveError> {
+    let values = raw.split(':').collect::<Vec<_>>();
+    if !(3..=5).contains(&values.len()) {
+      return Err(SolveError::InvalidRing(raw.to_string()));
+    }
+
+    match (
+      i8::from_str(values[0]),
+      u8::from_str(values[1]),
+      i8::from_str(values[2]),
+      values
+        .get(3)
+        .map_or(Ok(default_positions()), |it| u8::from_str(it)),
+      values.get(4).map_or(Ok(0), |it| i8::from_str(it)),
+    ) {
+      (Ok(current), Ok(num), Ok(direction), Ok(positions), Ok(target)) => {
+        Ok(Ring::new(current, num, direction, positions).with_target(target))
+      }
+      _ => Err(SolveError::InvalidRing(raw.to_string())),
     }
   }
+
+  pub fn with_target(mut self, target: i8) -> Self {
+    self.target = target;
+    self
+  }
+
+  pub fn is_solved(&self) -> bool {
+    self.current == self.target
+  }
+
+  /// 1 to 128 positions, current and target value in range and direction is either 1 or -1,
+  /// index is only used in the error
+  pub fn check(&self, index: usize) -> Result<(), SolveError> {
+    if !(1..=128).contains(&self.positions) {
+      return Err(SolveError::InvalidPositions {
+        ring: index,
+        positions: self.positions,
+      });
+    }
+    if self.direction != 1 && self.direction != -1 {
+      return Err(SolveError::InvalidDirection {
+        ring: index,
+        direction: self.direction,
+      });
+    }
+    if !(0..self.positions as i16).contains(&(self.current as i16)) {
+      return Err(SolveError::CurrentOutOfRange {
+        ring: index,
+        current: self.current,
+        positions: self.positions,
+      });
+    }
+    if !(0..self.positions as i16).contains(&(self.target as i16)) {
+      return Err(SolveError::TargetOutOfRange {
+        ring: index,
+        target: self.target,
+        positions: self.positions,
+      });
+    }
+
+    Ok(())
+  }
+
+  pub fn rotate(&mut self) {
+    let current = self.current as i16 + self.direction as i16 * self.num as i16;
+    self.current = current.rem_euclid(self.positions as i16) as i8;
+  }
+}
+
+pub(crate) fn gcd(a: u32, b: u32) -> u32 {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
 }
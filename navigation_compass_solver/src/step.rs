@@ -0,0 +1,10 @@
+use alloc::vec::Vec;
+
+use crate::action::Action;
+
+/// an action, and the value of every ring after it's done
+#[derive(Debug, Clone)]
+pub struct Step {
+  pub action: Action,
+  pub rings: Vec<i8>,
+}
@@ -0,0 +1,96 @@
+//! O(1) lookup of the shortest solution, from the table generated by the build script,
+//! for compasses with 3 rings of 6 positions moving 1 to 5 positions per rotation
+
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+
+use crate::{
+  action::Action, error::SolveError, linkage::Linkage, navigation_compass::NavigationCompass,
+};
+
+static TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/table.bin"));
+
+const POSITIONS: i16 = 6;
+const STATES: usize = 216;
+const SUBSETS: usize = 128;
+
+const SOLVED: u8 = 0;
+const UNSOLVABLE: u8 = 0xF;
+
+/// solve with the table if the compass and linkages are covered, otherwise [NavigationCompass::try_solve]
+pub fn solve(
+  compass: &NavigationCompass,
+  available_linkages: Vec<Linkage>,
+) -> Result<Vec<Action>, SolveError> {
+  lookup(compass, &available_linkages).unwrap_or_else(|| compass.try_solve(available_linkages))
+}
+
+/// shortest solution from the table, the same as [NavigationCompass::try_solve] in length,
+/// None if the compass or linkages are not covered by the table
+pub fn lookup(
+  compass: &NavigationCompass,
+  available_linkages: &[Linkage],
+) -> Option<Result<Vec<Action>, SolveError>> {
+  if let Err(err) = compass.check(available_linkages) {
+    return Some(Err(err));
+  }
+
+  let rings = compass.rings();
+  if rings.len() != 3 || rings.iter().any(|it| it.positions != POSITIONS as u8) {
+    return None;
+  }
+  if available_linkages
+    .iter()
+    .any(|it| it.mask == 0 || !it.overrides.is_empty())
+  {
+    return None;
+  }
+
+  // rotating by -num is the same as rotating by num to the opposite direction,
+  // so each ring is described by a num in 1..=3, and the value to move with that num
+  let mut nums = [0; 3];
+  let mut moves = [0; 3];
+  for (idx, ring) in rings.iter().enumerate() {
+    let num = (ring.direction as i16 * ring.num as i16).rem_euclid(POSITIONS);
+    let to_move = (ring.target as i16 - ring.current as i16).rem_euclid(POSITIONS);
+
+    (nums[idx], moves[idx]) = match num {
+      1..=3 => (num, to_move),
+      4..=5 => (POSITIONS - num, (POSITIONS - to_move) % POSITIONS),
+      _ => return None,
+    };
+  }
+
+  let nums_index = ((nums[0] - 1) * 9 + (nums[1] - 1) * 3 + (nums[2] - 1)) as usize;
+  let subset = available_linkages
+    .iter()
+    .fold(0, |acc, it| acc | 1 << (it.mask - 1));
+
+  let mut counts = [0; 8];
+  loop {
+    let moves_index = (moves[0] * 36 + moves[1] * 6 + moves[2]) as usize;
+    let index = (nums_index * STATES + moves_index) * SUBSETS + subset;
+
+    match TABLE[index / 2] >> (index % 2 * 4) & 0xF {
+      SOLVED => break,
+      UNSOLVABLE => return Some(Err(SolveError::Unsolvable)),
+      mask => {
+        counts[mask as usize] += 1;
+        for (idx, value) in moves.iter_mut().enumerate() {
+          if mask & (0b100 >> idx) > 0 {
+            *value = (*value - nums[idx]).rem_euclid(POSITIONS);
+          }
+        }
+      }
+    }
+  }
+
+  Some(Ok(
+    available_linkages
+      .iter()
+      .flat_map(|it| core::iter::repeat_n(it.clone(), counts[it.mask as usize]))
+      .map(Action::Rotate)
+      .collect_vec(),
+  ))
+}
@@ -0,0 +1,343 @@
+//! interactive terminal ui, edit the puzzle with the solution updated live,
+//! or play the solution step by step
+
+use std::error::Error;
+use std::io::{self, Write};
+
+use crossterm::{
+  cursor,
+  event::{self, Event, KeyCode, KeyEventKind},
+  execute, queue,
+  style::{Attribute, Print, SetAttribute},
+  terminal,
+};
+use itertools::Itertools;
+
+use navigation_compass_solver::action::Action;
+use navigation_compass_solver::linkage::{Linkage, Override};
+use navigation_compass_solver::navigation_compass::NavigationCompass;
+use navigation_compass_solver::ring::Ring;
+
+use crate::draw_dial;
+
+const RING_FIELDS: [&str; 5] = ["current", "num", "direction", "positions", "target"];
+
+enum Mode {
+  Edit,
+  /// steps of the solution are applied one by one to the compass
+  Play {
+    actions: Vec<Action>,
+    done: usize,
+    compass: NavigationCompass,
+  },
+}
+
+struct App {
+  rings: Vec<Ring>,
+  linkages: Vec<Linkage>,
+  /// selected row, rings first, then linkages
+  row: usize,
+  /// selected field of a ring, or ring of a linkage
+  column: usize,
+  mode: Mode,
+}
+
+/// a piece of a line, highlighted if selected
+type Span = (String, bool);
+
+/// restores the terminal when dropped, even if the ui failed
+struct Terminal;
+
+impl Terminal {
+  fn enter() -> io::Result<Self> {
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+    Ok(Self)
+  }
+}
+
+impl Drop for Terminal {
+  fn drop(&mut self) {
+    let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+  }
+}
+
+pub fn run(compass: NavigationCompass, linkages: Vec<Linkage>) -> Result<(), Box<dyn Error>> {
+  let mut app = App {
+    rings: compass.rings().to_vec(),
+    linkages,
+    row: 0,
+    column: 0,
+    mode: Mode::Edit,
+  };
+
+  let _terminal = Terminal::enter()?;
+  loop {
+    draw(&app.lines())?;
+
+    let Event::Key(key) = event::read()? else {
+      continue;
+    };
+    if key.kind != KeyEventKind::Press {
+      continue;
+    }
+
+    let quit = match app.mode {
+      Mode::Edit => app.edit(key.code),
+      Mode::Play { .. } => app.play(key.code),
+    };
+    if quit {
+      return Ok(());
+    }
+  }
+}
+
+fn draw(lines: &[Vec<Span>]) -> io::Result<()> {
+  let mut stdout = io::stdout();
+  queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+
+  for (y, line) in lines.iter().enumerate() {
+    queue!(stdout, cursor::MoveTo(0, y as u16))?;
+    for (text, selected) in line {
+      if *selected {
+        queue!(
+          stdout,
+          SetAttribute(Attribute::Reverse),
+          Print(text),
+          SetAttribute(Attribute::NoReverse)
+        )?;
+      } else {
+        queue!(stdout, Print(text))?;
+      }
+    }
+  }
+
+  stdout.flush()
+}
+
+impl App {
+  /// handle a key in edit mode, true to quit
+  fn edit(&mut self, key: KeyCode) -> bool {
+    let rows = self.rings.len() + self.linkages.len();
+    let columns = if self.row < self.rings.len() {
+      RING_FIELDS.len()
+    } else {
+      self.rings.len()
+    };
+
+    match key {
+      KeyCode::Char('q') | KeyCode::Esc => return true,
+      KeyCode::Up => self.row = (self.row + rows - 1) % rows,
+      KeyCode::Down => self.row = (self.row + 1) % rows,
+      KeyCode::Tab => self.column = (self.column + 1) % columns,
+      KeyCode::BackTab => self.column = (self.column + columns - 1) % columns,
+      KeyCode::Left => self.change(-1),
+      KeyCode::Right => self.change(1),
+      KeyCode::Char('a') => {
+        self.linkages.push(Linkage::new(0, self.rings.len() as u8));
+        self.row = rows;
+      }
+      KeyCode::Char('d') if self.row >= self.rings.len() && self.linkages.len() > 1 => {
+        self.linkages.remove(self.row - self.rings.len());
+        self.row = self.row.min(rows - 2);
+      }
+      KeyCode::Enter => {
+        let compass = NavigationCompass::new(self.rings.clone());
+        if let Ok(actions) = compass.try_solve(self.linkages.clone()) {
+          self.mode = Mode::Play {
+            actions,
+            done: 0,
+            compass,
+          };
+        }
+      }
+      _ => {}
+    }
+
+    let columns = if self.row < self.rings.len() {
+      RING_FIELDS.len()
+    } else {
+      self.rings.len()
+    };
+    self.column = self.column.min(columns - 1);
+
+    false
+  }
+
+  /// change the selected field of a ring, or the selected ring of a linkage
+  /// between not linked, linked and linked in the opposite direction
+  fn change(&mut self, delta: i16) {
+    if let Some(ring) = self.rings.get_mut(self.row) {
+      let positions = ring.positions as i16;
+      match self.column {
+        0 => ring.current = (ring.current as i16 + delta).rem_euclid(positions) as i8,
+        1 => ring.num = (ring.num as i16 + delta).rem_euclid(positions) as u8,
+        2 => ring.direction = -ring.direction,
+        3 => {
+          ring.positions = (positions + delta).clamp(1, 128) as u8;
+          ring.current = (ring.current as i16 % ring.positions as i16) as i8;
+          ring.target = (ring.target as i16 % ring.positions as i16) as i8;
+        }
+        _ => ring.target = (ring.target as i16 + delta).rem_euclid(positions) as i8,
+      }
+      return;
+    }
+
+    let linkage = &mut self.linkages[self.row - self.rings.len()];
+    let ring = self.column;
+    let bit = 1 << (linkage.width as usize - 1 - ring);
+    let symbol = match linkage.overrides.get(&ring) {
+      _ if !linkage.contains(ring) => 0,
+      None => 1,
+      Some(_) => 2,
+    };
+
+    linkage.mask &= !bit;
+    linkage.overrides.remove(&ring);
+    match (symbol + delta).rem_euclid(3) {
+      1 => linkage.mask |= bit,
+      2 => {
+        *linkage = linkage
+          .clone()
+          .with_override(ring, Override::Reverse)
+          .expect("ring of the compass")
+      }
+      _ => {}
+    }
+  }
+
+  /// handle a key in play mode, true to quit
+  fn play(&mut self, key: KeyCode) -> bool {
+    let Mode::Play {
+      actions,
+      done,
+      compass,
+    } = &mut self.mode
+    else {
+      return false;
+    };
+
+    match key {
+      KeyCode::Char('q') => return true,
+      KeyCode::Esc | KeyCode::Enter => self.mode = Mode::Edit,
+      KeyCode::Right | KeyCode::Char(' ') if *done < actions.len() => {
+        compass.apply(&actions[*done]);
+        *done += 1;
+      }
+      KeyCode::Left if *done > 0 => {
+        *done -= 1;
+        *compass = NavigationCompass::new(self.rings.clone());
+        actions[..*done].iter().for_each(|it| compass.apply(it));
+      }
+      _ => {}
+    }
+
+    false
+  }
+
+  fn lines(&self) -> Vec<Vec<Span>> {
+    let text = |it: String| vec![(it, false)];
+
+    match &self.mode {
+      Mode::Edit => {
+        let mut lines = vec![
+          text("edit: up/down select, tab select field, left/right change,".to_string()),
+          text("      a add linkage, d delete linkage, enter play, q quit".to_string()),
+          vec![],
+        ];
+
+        for (idx, ring) in self.rings.iter().enumerate() {
+          let values = [
+            ring.current as i16,
+            ring.num as i16,
+            ring.direction as i16,
+            ring.positions as i16,
+            ring.target as i16,
+          ];
+          let mut line = vec![(format!("ring {idx:<2}"), false)];
+          for (column, (name, value)) in RING_FIELDS.iter().zip(values).enumerate() {
+            line.push(("  ".to_string(), false));
+            let selected = self.row == idx && self.column == column;
+            line.push((format!("{name} {value:<2}"), selected));
+          }
+          line.push((format!("  {}", draw_dial(ring, ring.current)), false));
+          lines.push(line);
+        }
+
+        for (idx, linkage) in self.linkages.iter().enumerate() {
+          let row = self.rings.len() + idx;
+          let symbols = format!("{linkage:?}");
+          let mut line = vec![(format!("linkage {:<2} ", idx + 1), false)];
+          // one symbol for each ring unless a step override makes it longer
+          if symbols.len() == self.rings.len() {
+            line.extend(
+              symbols
+                .chars()
+                .enumerate()
+                .map(|(ring, it)| (it.to_string(), self.row == row && self.column == ring)),
+            );
+          } else {
+            line.push((symbols, self.row == row));
+          }
+          lines.push(line);
+        }
+
+        lines.push(vec![]);
+        lines.push(text(self.solution()));
+        lines
+      }
+      Mode::Play {
+        actions,
+        done,
+        compass,
+      } => {
+        let mut lines = vec![
+          text("play: right/space next step, left previous step, enter edit, q quit".to_string()),
+          vec![],
+        ];
+
+        for (idx, ring) in compass.rings().iter().enumerate() {
+          lines.push(text(format!(
+            "ring {idx:<2} {:<3} {}",
+            ring.current,
+            draw_dial(ring, ring.current)
+          )));
+        }
+
+        lines.push(vec![]);
+        for (idx, action) in actions.iter().enumerate() {
+          lines.push(vec![(
+            format!("step {}: {action:?}", idx + 1),
+            idx == *done,
+          )]);
+        }
+        lines.push(vec![]);
+        if compass.is_solved() {
+          lines.push(text("solved".to_string()));
+        } else {
+          lines.push(text(format!("{done} of {} steps", actions.len())));
+        }
+        lines
+      }
+    }
+  }
+
+  /// solution of the current input, updated live
+  fn solution(&self) -> String {
+    let compass = NavigationCompass::new(self.rings.clone());
+    match compass.try_solve(self.linkages.clone()) {
+      Ok(actions) if actions.is_empty() => "already solved".to_string(),
+      Ok(actions) => {
+        let counts = actions
+          .iter()
+          .map(|it| format!("{it:?}"))
+          .dedup_with_count()
+          .map(|(count, linkage)| format!("{linkage} x{count}"))
+          .join(", ");
+        format!("solution ({} steps): {counts}", actions.len())
+      }
+      Err(err) => err.to_string(),
+    }
+  }
+}
@@ -0,0 +1,164 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+const SOLVABLE: &str = "4:1:-1,0:3:-1,0:3:1/011,101,110";
+const UNSOLVABLE: &str = "1:1:1,1:1:1/10";
+const TOO_LARGE: &str = "1:1:1:128,1:1:1:127,1:1:1:125/111";
+
+/// exit code, stdout and stderr of the binary with the arguments
+fn output(args: &[&str]) -> (i32, String, String) {
+  let output = Command::new(env!("CARGO_BIN_EXE_navigation_compass_solver"))
+    .args(args)
+    .output()
+    .expect("binary runs");
+
+  (
+    output.status.code().expect("exited"),
+    String::from_utf8(output.stdout).unwrap(),
+    String::from_utf8(output.stderr).unwrap(),
+  )
+}
+
+/// exit code and stdout of the binary with the arguments
+fn run(args: &[&str]) -> (i32, String) {
+  let (code, stdout, _) = output(args);
+  (code, stdout)
+}
+
+fn json(args: &[&str]) -> (i32, serde_json::Value) {
+  let (code, stdout) = run(args);
+  (code, serde_json::from_str(&stdout).unwrap())
+}
+
+#[test]
+fn exit_codes() {
+  assert_eq!(run(&[SOLVABLE]).0, 0);
+  assert_eq!(run(&["--unknown"]).0, 1);
+  assert_eq!(run(&["4:1:-1/2"]).0, 2);
+  assert_eq!(run(&["--top", "0", SOLVABLE]).0, 2);
+  assert_eq!(run(&[UNSOLVABLE]).0, 3);
+  assert_eq!(run(&[TOO_LARGE]).0, 4);
+  assert_eq!(run(&["--batch", "missing/puzzles.json"]).0, 5);
+
+  let path = std::env::temp_dir().join(format!("batch-{}.json", std::process::id()));
+  std::fs::write(
+    &path,
+    r#"{"puzzles": [
+      {"name": "solvable", "rings": [{"current": 4, "num": 1, "direction": -1}], "linkages": ["1"]},
+      {"name": "unsolvable", "rings": [{"current": 1, "num": 2, "direction": 1}], "linkages": ["1"]}
+    ]}"#,
+  )
+  .unwrap();
+  let (code, stdout, stderr) = output(&["--batch", path.to_str().unwrap()]);
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(code, 6, "{stdout}");
+  assert!(stdout.starts_with("solvable: 4 steps\n"), "{stdout}");
+  assert_eq!(stderr, "1 of 2 puzzles failed\n");
+}
+
+/// errors go to stderr, stdout only has results
+#[test]
+fn errors_on_stderr() {
+  for (args, expected) in [
+    (&[UNSOLVABLE][..], "no solution\n"),
+    (&["4:1:-1/2"], "failed to parse linkage: 2\n"),
+    (&["--hint", UNSOLVABLE], "no solution\n"),
+  ] {
+    let (_, stdout, stderr) = output(args);
+    assert_eq!(stdout, "", "{args:?}");
+    assert_eq!(stderr, expected, "{args:?}");
+  }
+}
+
+#[test]
+fn json_report() {
+  let (code, report) = json(&["--format", "json", SOLVABLE]);
+  assert_eq!(code, 0);
+  assert_eq!(report["puzzle"], SOLVABLE);
+  assert_eq!(report["initial"], serde_json::json!([4, 0, 0]));
+  let steps = report["steps"].as_array().unwrap();
+  assert_eq!(steps.len(), 4);
+  assert_eq!(steps[3]["rings"], serde_json::json!([0, 0, 0]));
+  assert!(report.get("clicks").is_none());
+  assert!(report.get("error").is_none());
+
+  let (code, report) = json(&["--format", "json", "--cost", "2", SOLVABLE]);
+  assert_eq!(code, 0);
+  assert_eq!(report["clicks"], 8);
+
+  for (puzzle, expected, kind) in [
+    ("4:1:-1/2", 2, "invalid_input"),
+    (UNSOLVABLE, 3, "unsolvable"),
+    (TOO_LARGE, 4, "search_limit_exceeded"),
+  ] {
+    let (code, report) = json(&["--format", "json", puzzle]);
+    assert_eq!(code, expected, "{puzzle}");
+    assert_eq!(report["error"]["code"], expected, "{puzzle}");
+    assert_eq!(report["error"]["kind"], kind, "{puzzle}");
+    assert!(report.get("steps").is_none(), "{puzzle}");
+  }
+}
+
+#[test]
+fn csv_report() {
+  // the puzzle is quoted for its commas
+  let (code, csv) = run(&["--format", "csv", "4:1:1,2:2:1/11"]);
+  assert_eq!(code, 0);
+  assert_eq!(
+    csv,
+    "puzzle,step,linkage,ring 0,ring 1\n\
+     \"4:1:1,2:2:1/11\",0,,4,2\n\
+     \"4:1:1,2:2:1/11\",1,11,5,4\n\
+     \"4:1:1,2:2:1/11\",2,11,0,0\n"
+  );
+
+  // 1 rotation of 3 clicks and 2 clicks to switch to it
+  let (code, csv) = run(&[
+    "--format",
+    "csv",
+    "--cost",
+    "3",
+    "--switch-cost",
+    "2",
+    "5:1:1/1",
+  ]);
+  assert_eq!(code, 0);
+  assert_eq!(
+    csv,
+    "puzzle,step,linkage,ring 0,clicks\n\
+     5:1:1/1,0,,5,5\n\
+     5:1:1/1,1,1,0,5\n"
+  );
+
+  let (code, csv) = run(&["--format", "csv", UNSOLVABLE]);
+  assert_eq!(code, 3);
+  assert!(csv.starts_with("puzzle,error,code,message\n"), "{csv}");
+  assert!(csv.contains(",unsolvable,3,no solution"), "{csv}");
+}
+
+/// --format only applies to solving a single puzzle, other modes are invalid arguments
+#[test]
+fn format_rejected_in_other_modes() {
+  for mode in [
+    &["--hint"][..],
+    &["--all"],
+    &["--top", "2"],
+    &["--explain"],
+    &["--notation"],
+    &["--trace"],
+    &["--dial"],
+    &["--batch", "puzzles.json"],
+    &["--generate", "1"],
+  ] {
+    for format in ["json", "csv"] {
+      let args = [&["--format", format, SOLVABLE][..], mode].concat();
+      let (code, stdout) = run(&args);
+      assert_eq!(code, 1, "{args:?}");
+      assert!(stdout.is_empty(), "{args:?}");
+    }
+  }
+
+  // text is the default and works everywhere
+  assert_eq!(run(&["--format", "text", "--hint", SOLVABLE]).0, 0);
+}
@@ -0,0 +1,94 @@
+use navigation_compass_solver::error::SolveError;
+use navigation_compass_solver::linkage::{Linkage, Override};
+use navigation_compass_solver::navigation_compass::NavigationCompass;
+use navigation_compass_solver::puzzle::CompassPuzzle;
+use navigation_compass_solver::ring::Ring;
+
+/// printed the same after parsed again
+fn round_trip<T>(raw: &str) -> T
+where
+  T: std::str::FromStr + std::fmt::Display,
+  T::Err: std::fmt::Debug,
+{
+  let parsed = raw.parse::<T>().unwrap();
+  let printed = parsed.to_string();
+  assert_eq!(printed.parse::<T>().unwrap().to_string(), printed, "{raw}");
+  parsed
+}
+
+#[test]
+fn ring_round_trip() {
+  for (raw, printed, [current, num, direction, positions, target]) in [
+    ("4:1:-1", "4:1:-1", [4, 1, -1, 6, 0]),
+    ("4:1:-1:8", "4:1:-1:8", [4, 1, -1, 8, 0]),
+    ("4:1:-1:6:3", "4:1:-1:6:3", [4, 1, -1, 6, 3]),
+    ("4:1:-1:6:0", "4:1:-1", [4, 1, -1, 6, 0]),
+    ("127:2:1:128:127", "127:2:1:128:127", [127, 2, 1, 128, 127]),
+  ] {
+    let ring = round_trip::<Ring>(raw);
+    assert_eq!(ring.to_string(), printed);
+    assert_eq!(
+      [
+        ring.current as i16,
+        ring.num as i16,
+        ring.direction as i16,
+        ring.positions as i16,
+        ring.target as i16,
+      ],
+      [current, num, direction, positions, target],
+      "{raw}"
+    );
+  }
+}
+
+#[test]
+fn linkage_round_trip() {
+  for raw in ["1", "101", "011", "1-0", "10[2]", "1[2][-1]", "-[-3]0[0]"] {
+    let linkage = round_trip::<Linkage>(raw);
+    assert_eq!(linkage.to_string(), raw);
+  }
+
+  let linkage = round_trip::<Linkage>("1-0[2]");
+  assert_eq!(linkage.mask, 0b1101);
+  assert_eq!(linkage.width, 4);
+  assert_eq!(
+    linkage.overrides.into_iter().collect::<Vec<_>>(),
+    [(1, Override::Reverse), (3, Override::Step(2))]
+  );
+}
+
+#[test]
+fn override_out_of_range() {
+  let linkage = Linkage::new(0b101, 3);
+  assert_eq!(
+    linkage
+      .clone()
+      .with_override(2, Override::Reverse)
+      .unwrap()
+      .to_string(),
+    "10-"
+  );
+  assert_eq!(
+    linkage.with_override(3, Override::Reverse).err(),
+    Some(SolveError::OverrideOutOfRange { ring: 3, width: 3 })
+  );
+  assert_eq!(
+    Linkage::new(0, 0).with_override(0, Override::Step(1)).err(),
+    Some(SolveError::OverrideOutOfRange { ring: 0, width: 0 })
+  );
+}
+
+#[test]
+fn compass_and_puzzle_round_trip() {
+  let compass = round_trip::<NavigationCompass>("4:1:-1,0:3:-1:8,0:3:1:6:2");
+  assert_eq!(compass.to_string(), "4:1:-1,0:3:-1:8,0:3:1:6:2");
+  assert_eq!(compass.rings().len(), 3);
+
+  for raw in [
+    "4:1:-1,0:3:-1,0:3:1/011,101,110",
+    "4:1:-1,0:3:-1:8,0:3:1:6:2/1-0,10[2],[-1]11",
+  ] {
+    let puzzle = round_trip::<CompassPuzzle>(raw);
+    assert_eq!(puzzle.to_string(), raw);
+  }
+}
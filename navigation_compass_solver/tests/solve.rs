@@ -0,0 +1,538 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use itertools::Itertools;
+
+use navigation_compass_solver::action::Action;
+use navigation_compass_solver::cost::Cost;
+use navigation_compass_solver::error::SolveError;
+use navigation_compass_solver::linkage::{Linkage, Override};
+use navigation_compass_solver::navigation_compass::{NavigationCompass, SEARCH_LIMIT};
+use navigation_compass_solver::puzzle::CompassPuzzle;
+use navigation_compass_solver::ring::Ring;
+use puzzle_search::error::SearchError;
+use puzzle_search::puzzle::Puzzle;
+use puzzle_search::{astar::astar, bfs::bfs, dfs::dfs, iddfs::iddfs};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn puzzle(raw: &str) -> CompassPuzzle {
+  raw.parse().unwrap()
+}
+
+/// a random puzzle of the rings, each with positions from the list,
+/// with distinct linkages that sometimes override how a ring moves
+fn random_puzzle(rng: &mut ChaCha8Rng, rings: usize, positions: &[u8]) -> CompassPuzzle {
+  let rings = (0..rings)
+    .map(|_| {
+      let positions = positions[rng.gen_range(0..positions.len())];
+      let random = |rng: &mut ChaCha8Rng| rng.gen_range(0..positions) as i8;
+      let direction = if rng.gen_bool(0.5) { 1 } else { -1 };
+
+      Ring::new(random(rng), random(rng) as u8, direction, positions).with_target(random(rng))
+    })
+    .collect_vec();
+
+  let width = rings.len() as u8;
+  let count = rng.gen_range(1..=4.min((1 << width) - 1));
+  let mut masks = vec![];
+  while masks.len() < count {
+    let mask = rng.gen_range(1..1 << width);
+    if !masks.contains(&mask) {
+      masks.push(mask);
+    }
+  }
+  let linkages = masks
+    .into_iter()
+    .map(|mask| {
+      let linkage = Linkage::new(mask, width);
+      let ring = rng.gen_range(0..width as usize);
+      match rng.gen_range(0..6) {
+        _ if !linkage.contains(ring) => linkage,
+        0 => linkage.with_override(ring, Override::Reverse).unwrap(),
+        1 => linkage
+          .with_override(ring, Override::Step(rng.gen_range(-2..=2)))
+          .unwrap(),
+        _ => linkage,
+      }
+    })
+    .collect_vec();
+
+  let mut puzzle = CompassPuzzle::new(NavigationCompass::new(rings), linkages);
+
+  // most are unsolvable, scramble half of them from the target to be solvable
+  if rng.gen_bool(0.5) {
+    let mut state = target(&puzzle.compass);
+    for _ in 0..rng.gen_range(0..10) {
+      let linkage = &puzzle.linkages[rng.gen_range(0..puzzle.linkages.len())];
+      state = rotate(&puzzle.compass, &state, linkage);
+    }
+    puzzle.compass = with_state(&puzzle.compass, &state);
+  }
+
+  puzzle
+}
+
+fn with_state(compass: &NavigationCompass, state: &[i8]) -> NavigationCompass {
+  NavigationCompass::new(
+    compass
+      .rings()
+      .iter()
+      .zip(state)
+      .map(|(ring, current)| Ring {
+        current: *current,
+        ..ring.clone()
+      })
+      .collect_vec(),
+  )
+}
+
+/// value of every ring after rotating once with the linkage
+fn rotate(compass: &NavigationCompass, state: &[i8], linkage: &Linkage) -> Vec<i8> {
+  let mut compass = with_state(compass, state);
+  compass.apply(&Action::Rotate(linkage.clone()));
+  compass.rings().iter().map(|it| it.current).collect_vec()
+}
+
+fn state(compass: &NavigationCompass) -> Vec<i8> {
+  compass.rings().iter().map(|it| it.current).collect_vec()
+}
+
+fn target(compass: &NavigationCompass) -> Vec<i8> {
+  compass.rings().iter().map(|it| it.target).collect_vec()
+}
+
+/// least rotations to the target by breadth first search over every state, None if unreachable
+fn least_rotations(puzzle: &CompassPuzzle) -> Option<usize> {
+  let target = target(&puzzle.compass);
+  let mut distance = BTreeMap::from([(state(&puzzle.compass), 0)]);
+  let mut queue = VecDeque::from([state(&puzzle.compass)]);
+
+  while let Some(state) = queue.pop_front() {
+    let current = distance[&state];
+    if state == target {
+      return Some(current);
+    }
+
+    for linkage in &puzzle.linkages {
+      let next = rotate(&puzzle.compass, &state, linkage);
+      distance.entry(next.clone()).or_insert_with(|| {
+        queue.push_back(next);
+        current + 1
+      });
+    }
+  }
+
+  None
+}
+
+/// the actions get every ring to its target
+fn solves(compass: &NavigationCompass, actions: &[Action]) -> bool {
+  let mut compass = compass.clone();
+  actions.iter().for_each(|it| compass.apply(it));
+  compass.is_solved()
+}
+
+/// the solver finds a solution as short as breadth first search,
+/// for several numbers of rings, positions, directions, targets and overrides
+#[test]
+fn try_solve_matches_brute_force() {
+  let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+  for rings in 1..=4 {
+    for positions in [&[2, 3, 4][..], &[6], &[5, 6, 8]] {
+      for _ in 0..60 {
+        let puzzle = random_puzzle(&mut rng, rings, positions);
+        let solved = puzzle.compass.try_solve(puzzle.linkages.clone());
+
+        match least_rotations(&puzzle) {
+          Some(least) => {
+            let actions = solved.unwrap_or_else(|err| panic!("{puzzle}: {err}"));
+            assert_eq!(actions.len(), least, "{puzzle}");
+            assert!(solves(&puzzle.compass, &actions), "{puzzle}");
+          }
+          None => assert_eq!(solved.err(), Some(SolveError::Unsolvable), "{puzzle}"),
+        }
+      }
+    }
+  }
+}
+
+/// periods of rings with coprime positions multiply past the limit, fail before searching
+#[test]
+fn large_period_exceeds_search_limit() {
+  let limit = Some(SolveError::SearchLimitExceeded {
+    limit: SEARCH_LIMIT,
+  });
+
+  for raw in [
+    "1:1:1:128,1:1:1:127,1:1:1:125,1:1:1:121,1:1:1:113/11111",
+    "1:1:1:128,1:1:1:127,1:1:1:125/111",
+  ] {
+    let CompassPuzzle { compass, linkages } = puzzle(raw);
+    assert_eq!(compass.period(&linkages[0]).err(), limit);
+    assert_eq!(compass.try_solve(linkages).err(), limit);
+  }
+}
+
+/// values a ring never gets to are listed, up to the last position of a 128 position ring
+#[test]
+fn analyze_unsolvable() {
+  let CompassPuzzle { compass, linkages } = puzzle("3:2:1:128/1");
+  let analysis = compass.analyze(&linkages).unwrap();
+  assert!(!analysis.solvable);
+  assert_eq!(analysis.reachable.len(), 64);
+  assert_eq!(
+    analysis.unreachable_values,
+    vec![(0..=126).step_by(2).collect::<Vec<i8>>()]
+  );
+  // the only linkage is already there
+  assert_eq!(analysis.suggestion, None);
+
+  // the outer ring never moves
+  let CompassPuzzle { compass, linkages } = puzzle("1:1:1,1:1:1/10");
+  let analysis = compass.analyze(&linkages).unwrap();
+  assert!(!analysis.solvable);
+  assert_eq!(analysis.unreachable_values[0], Vec::<i8>::new());
+  assert_eq!(analysis.unreachable_values[1], vec![0, 2, 3, 4, 5]);
+  assert_eq!(analysis.suggestion, Some("01".parse().unwrap()));
+}
+
+/// every linkage of 16 rings is tried against the reachable states once, not searched again each
+#[test]
+fn analyze_wide_compass() {
+  let single = |ring: usize| {
+    let mut linkage = vec!["0"; 16];
+    linkage[ring] = "1";
+    linkage.concat()
+  };
+  let rings = |first: &str, last: &str| {
+    [first]
+      .into_iter()
+      .chain(["0:1:1:2"; 14])
+      .chain([last])
+      .join(",")
+  };
+  let linkages = (1..15).map(single).join(",");
+
+  // the first ring never moves, nothing helps
+  let start = std::time::Instant::now();
+  let CompassPuzzle {
+    compass,
+    linkages: available,
+  } = puzzle(&format!("{}/{linkages}", rings("1:0:1:2", "0:1:1:2")));
+  let analysis = compass.analyze(&available).unwrap();
+  assert!(!analysis.solvable);
+  assert_eq!(analysis.reachable.len(), 1 << 14);
+  assert_eq!(analysis.suggestion, None);
+  assert!(start.elapsed().as_secs() < 10, "{:?}", start.elapsed());
+
+  // only the last ring is off and can't move
+  let CompassPuzzle {
+    compass,
+    linkages: available,
+  } = puzzle(&format!("{}/{linkages}", rings("0:1:1:2", "1:1:1:2")));
+  let analysis = compass.analyze(&available).unwrap();
+  assert_eq!(analysis.suggestion, Some(single(15).parse().unwrap()));
+}
+
+/// rotations with the linkage to get back to the same state
+fn period(compass: &NavigationCompass, linkage: &Linkage) -> u32 {
+  let start = state(compass);
+  let mut state = rotate(compass, &start, linkage);
+  let mut period = 1;
+  while state != start {
+    state = rotate(compass, &state, linkage);
+    period += 1;
+  }
+  period
+}
+
+/// every rotate count of each linkage, less than its period, that gets to the target
+fn all_counts(puzzle: &CompassPuzzle) -> Vec<Vec<u32>> {
+  let target = target(&puzzle.compass);
+
+  puzzle
+    .linkages
+    .iter()
+    .map(|it| 0..period(&puzzle.compass, it))
+    .multi_cartesian_product()
+    .filter(|counts| {
+      let mut state = state(&puzzle.compass);
+      for (linkage, count) in puzzle.linkages.iter().zip(counts) {
+        for _ in 0..*count {
+          state = rotate(&puzzle.compass, &state, linkage);
+        }
+      }
+      state == target
+    })
+    .collect_vec()
+}
+
+/// rotate count of each linkage in the actions
+fn counts_of(linkages: &[Linkage], actions: &[Action]) -> Vec<u32> {
+  linkages
+    .iter()
+    .map(|linkage| {
+      actions
+        .iter()
+        .filter(|Action::Rotate(it)| it == linkage)
+        .count() as u32
+    })
+    .collect_vec()
+}
+
+/// the least clicks of every rotate count that solves, switching once to each linkage used
+#[test]
+fn try_solve_with_cost_matches_brute_force() {
+  let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+  for rings in 1..=3 {
+    for positions in [&[2, 4][..], &[6]] {
+      for _ in 0..60 {
+        let puzzle = random_puzzle(&mut rng, rings, positions);
+        let linkages = puzzle.linkages.len();
+        let cost = Cost::new(
+          (0..linkages).map(|_| rng.gen_range(1..=4)).collect_vec(),
+          (0..linkages).map(|_| rng.gen_range(0..6)).collect_vec(),
+        );
+        let clicks = |counts: &[u32]| {
+          counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(idx, count)| (cost.switch[idx] + cost.rotate[idx] * count) as u64)
+            .sum::<u64>()
+        };
+
+        let solved = puzzle
+          .compass
+          .try_solve_with_cost(puzzle.linkages.clone(), &cost);
+
+        match all_counts(&puzzle).iter().map(|it| clicks(it)).min() {
+          Some(least) => {
+            let (actions, total) = solved.unwrap_or_else(|err| panic!("{puzzle}: {err}"));
+            assert_eq!(total, least, "{puzzle} {cost:?}");
+            assert_eq!(clicks(&counts_of(&puzzle.linkages, &actions)), total);
+            assert!(solves(&puzzle.compass, &actions), "{puzzle}");
+            // actions of a linkage are grouped together
+            assert!(
+              actions
+                .iter()
+                .map(|Action::Rotate(it)| it)
+                .dedup()
+                .all_unique(),
+              "{puzzle}"
+            );
+          }
+          None => assert_eq!(solved.err(), Some(SolveError::Unsolvable), "{puzzle}"),
+        }
+      }
+    }
+  }
+}
+
+/// every minimal solution and the shortest n are the same as from every rotate count that solves
+#[test]
+fn ranked_solutions_match_brute_force() {
+  let mut rng = ChaCha8Rng::seed_from_u64(3);
+
+  for rings in 1..=3 {
+    for positions in [&[2, 4][..], &[6]] {
+      for _ in 0..60 {
+        let puzzle = random_puzzle(&mut rng, rings, positions);
+        let all = all_counts(&puzzle);
+        let rotations = |counts: &Vec<u32>| counts.iter().sum::<u32>();
+
+        let minimal = puzzle
+          .compass
+          .all_minimal_solutions(puzzle.linkages.clone());
+        let Some(least) = all.iter().map(rotations).min() else {
+          assert_eq!(minimal.err(), Some(SolveError::Unsolvable), "{puzzle}");
+          continue;
+        };
+
+        let expected = all
+          .iter()
+          .filter(|it| rotations(it) == least)
+          .cloned()
+          .sorted()
+          .collect_vec();
+        let minimal = minimal
+          .unwrap()
+          .iter()
+          .map(|it| counts_of(&puzzle.linkages, it))
+          .sorted()
+          .collect_vec();
+        assert_eq!(minimal, expected, "{puzzle}");
+
+        assert_eq!(
+          puzzle
+            .compass
+            .shortest_solutions(puzzle.linkages.clone(), 0)
+            .err(),
+          Some(SolveError::NoSolutionAsked)
+        );
+        for n in [1, 3, 10] {
+          let shortest = puzzle
+            .compass
+            .shortest_solutions(puzzle.linkages.clone(), n)
+            .unwrap();
+          let expected = all.iter().map(rotations).sorted().take(n).collect_vec();
+          assert_eq!(
+            shortest.iter().map(|it| it.len() as u32).collect_vec(),
+            expected,
+            "{puzzle}"
+          );
+          assert!(shortest.iter().all(|it| solves(&puzzle.compass, it)));
+          assert!(shortest
+            .iter()
+            .map(|it| counts_of(&puzzle.linkages, it))
+            .all_unique());
+        }
+      }
+    }
+  }
+}
+
+/// every search engine finds a solution as short as try_solve, or none
+#[test]
+fn search_engines_match_try_solve() {
+  let mut rng = ChaCha8Rng::seed_from_u64(4);
+
+  for rings in 1..=3 {
+    for positions in [&[2, 3, 4][..], &[6]] {
+      for _ in 0..40 {
+        let puzzle = random_puzzle(&mut rng, rings, positions);
+        let solved = puzzle.compass.try_solve(puzzle.linkages.clone());
+
+        let Ok(expected) = solved else {
+          assert_eq!(solved.err(), Some(SolveError::Unsolvable), "{puzzle}");
+          assert_eq!(
+            bfs(&puzzle, SEARCH_LIMIT).err(),
+            Some(SearchError::Unsolvable)
+          );
+          assert_eq!(
+            astar(&puzzle, SEARCH_LIMIT).err(),
+            Some(SearchError::Unsolvable)
+          );
+          assert_eq!(
+            dfs(&puzzle, SEARCH_LIMIT).err(),
+            Some(SearchError::Unsolvable)
+          );
+          continue;
+        };
+
+        let mut solutions = vec![
+          bfs(&puzzle, SEARCH_LIMIT).unwrap(),
+          astar(&puzzle, SEARCH_LIMIT).unwrap(),
+          dfs(&puzzle, SEARCH_LIMIT).unwrap(),
+        ];
+        // iddfs walks every path up to the depth, only for short solutions
+        if expected.len() <= 6 {
+          solutions.push(iddfs(&puzzle, expected.len()).unwrap());
+        }
+
+        for solution in solutions {
+          assert_eq!(solution.actions.len(), expected.len(), "{puzzle}");
+          assert_eq!(solution.cost, expected.len() as u64, "{puzzle}");
+          let actions = solution
+            .actions
+            .into_iter()
+            .map(Action::Rotate)
+            .collect_vec();
+          assert!(solves(&puzzle.compass, &actions), "{puzzle}");
+        }
+      }
+    }
+  }
+}
+
+/// a compass puzzle where rotating each linkage costs differently
+struct Weighted {
+  puzzle: CompassPuzzle,
+  costs: Vec<u64>,
+}
+
+impl Puzzle for Weighted {
+  type State = Vec<i8>;
+  type Action = Linkage;
+
+  fn initial(&self) -> Self::State {
+    self.puzzle.initial()
+  }
+
+  fn actions(&self, state: &Self::State) -> Vec<Self::Action> {
+    self.puzzle.actions(state)
+  }
+
+  fn apply(&self, state: &Self::State, action: &Self::Action) -> Self::State {
+    self.puzzle.apply(state, action)
+  }
+
+  fn is_goal(&self, state: &Self::State) -> bool {
+    self.puzzle.is_goal(state)
+  }
+
+  fn cost(&self, _state: &Self::State, action: &Self::Action) -> u64 {
+    let idx = self.puzzle.linkages.iter().position(|it| it == action);
+    self.costs[idx.unwrap()]
+  }
+
+  fn heuristic(&self, state: &Self::State) -> u64 {
+    match self.is_goal(state) {
+      true => 0,
+      false => *self.costs.iter().min().unwrap(),
+    }
+  }
+}
+
+/// A* and depth first search find the cheapest of every rotate count that solves
+#[test]
+fn weighted_search_matches_brute_force() {
+  let mut rng = ChaCha8Rng::seed_from_u64(5);
+
+  for rings in 1..=3 {
+    for positions in [&[2, 4][..], &[6]] {
+      for _ in 0..40 {
+        let puzzle = random_puzzle(&mut rng, rings, positions);
+        let costs = (0..puzzle.linkages.len())
+          .map(|_| rng.gen_range(1..=5))
+          .collect_vec();
+        let cheapest = all_counts(&puzzle)
+          .iter()
+          .map(|counts| {
+            counts
+              .iter()
+              .zip(&costs)
+              .map(|(count, cost)| *count as u64 * cost)
+              .sum()
+          })
+          .min();
+        let weighted = Weighted { puzzle, costs };
+
+        for solved in [astar(&weighted, SEARCH_LIMIT), dfs(&weighted, SEARCH_LIMIT)] {
+          let puzzle = &weighted.puzzle;
+          match cheapest {
+            Some(cheapest) => {
+              let solution = solved.unwrap_or_else(|err| panic!("{puzzle}: {err}"));
+              assert_eq!(solution.cost, cheapest, "{puzzle} {:?}", weighted.costs);
+              let actions = solution
+                .actions
+                .into_iter()
+                .map(Action::Rotate)
+                .collect_vec();
+              let total = counts_of(&puzzle.linkages, &actions)
+                .iter()
+                .zip(&weighted.costs)
+                .map(|(count, cost)| *count as u64 * cost)
+                .sum::<u64>();
+              assert_eq!(total, cheapest, "{puzzle}");
+              assert!(solves(&puzzle.compass, &actions), "{puzzle}");
+            }
+            None => assert_eq!(solved.err(), Some(SearchError::Unsolvable), "{puzzle}"),
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "table")]
+
+use itertools::Itertools;
+
+use navigation_compass_solver::error::SolveError;
+use navigation_compass_solver::linkage::Linkage;
+use navigation_compass_solver::navigation_compass::NavigationCompass;
+use navigation_compass_solver::ring::Ring;
+use navigation_compass_solver::table;
+
+/// every entry of the table, in both directions, gives a solution as short as the solver,
+/// or no solution if the solver can't reach it
+///
+/// an entry is decided by the value to move each ring, as rotations commute,
+/// so the least rotations to every state from zero, found once for each nums and linkages,
+/// are the least rotations to solve every entry with those nums and linkages
+#[test]
+fn table_matches_solver() {
+  for direction in [1, -1] {
+    for nums in 0..27 {
+      let nums = [nums / 9 + 1, nums / 3 % 3 + 1, nums % 3 + 1];
+      let compass = |target: [i8; 3]| {
+        NavigationCompass::new(
+          nums
+            .iter()
+            .zip(target)
+            .map(|(num, target)| Ring::new(0, *num, direction, 6).with_target(target))
+            .collect_vec(),
+        )
+      };
+
+      for subset in 1..128_u64 {
+        let linkages = (1..=7)
+          .filter(|mask| subset & (1 << (mask - 1)) > 0)
+          .map(|mask| Linkage::new(mask, 3))
+          .collect_vec();
+
+        let analysis = compass([0; 3]).analyze(&linkages).unwrap();
+        let rotations = analysis
+          .reachable
+          .into_iter()
+          .zip(analysis.rotations)
+          .collect::<std::collections::BTreeMap<_, _>>();
+
+        for target in (0..216_i16).map(|it| [it / 36, it / 6 % 6, it % 6].map(|it| it as i8)) {
+          let compass = compass(target);
+          let found = table::lookup(&compass, &linkages).expect("covered by table");
+
+          match rotations.get(&target[..]) {
+            Some(rotations) => {
+              let actions = found.unwrap_or_else(|err| panic!("{compass} {linkages:?}: {err}"));
+              assert_eq!(actions.len(), *rotations as usize, "{compass} {linkages:?}");
+              let mut solved = compass.clone();
+              actions.iter().for_each(|it| solved.apply(it));
+              assert!(solved.is_solved(), "{compass} {linkages:?}");
+            }
+            None => assert_eq!(
+              found.err(),
+              Some(SolveError::Unsolvable),
+              "{compass} {linkages:?}"
+            ),
+          }
+        }
+      }
+    }
+  }
+}
+
+/// compasses the table doesn't cover are left to the solver
+#[test]
+fn table_covers_three_rings_of_six() {
+  let linkages = [Linkage::new(0b111, 3)];
+  for rings in ["1:1:1:8,0:1:1:8,0:1:1:8", "1:6:1,0:1:1,0:1:1"] {
+    let compass = rings.parse::<NavigationCompass>().unwrap();
+    assert!(table::lookup(&compass, &linkages).is_none(), "{rings}");
+  }
+
+  let compass = "2:2:-1,2:2:-1,2:2:-1".parse::<NavigationCompass>().unwrap();
+  assert_eq!(
+    table::lookup(&compass, &linkages).unwrap().unwrap().len(),
+    1
+  );
+}
@@ -0,0 +1,56 @@
+use alloc::collections::{BTreeMap, BinaryHeap};
+use core::cmp::Reverse;
+
+use crate::{
+  error::SearchError,
+  puzzle::Puzzle,
+  solution::{self, Solution},
+};
+
+/// A* search for the cheapest actions by [Puzzle::cost], guided by [Puzzle::heuristic],
+/// gives up after reaching more than limit states
+pub fn astar<P: Puzzle>(puzzle: &P, limit: usize) -> Result<Solution<P::Action>, SearchError> {
+  let initial = puzzle.initial();
+
+  // cheapest known cost to each state, and where it came from
+  let mut costs = BTreeMap::new();
+  let mut parents = BTreeMap::new();
+  costs.insert(initial.clone(), 0);
+  parents.insert(initial.clone(), None);
+
+  let mut open = BinaryHeap::new();
+  open.push(Reverse((puzzle.heuristic(&initial), 0, initial)));
+
+  while let Some(Reverse((_, cost, state))) = open.pop() {
+    // outdated entry, the state was reached cheaper since pushed
+    if costs.get(&state).is_some_and(|it| *it < cost) {
+      continue;
+    }
+
+    if puzzle.is_goal(&state) {
+      return Ok(solution::from_parents(puzzle, &parents, state));
+    }
+
+    for action in puzzle.actions(&state) {
+      let next = puzzle.apply(&state, &action);
+      let next_cost = cost + puzzle.cost(&state, &action);
+
+      match costs.get(&next) {
+        Some(it) if *it <= next_cost => continue,
+        Some(_) => {}
+        None if costs.len() >= limit => return Err(SearchError::LimitExceeded { limit }),
+        None => {}
+      }
+
+      costs.insert(next.clone(), next_cost);
+      parents.insert(next.clone(), Some((state.clone(), action)));
+      open.push(Reverse((
+        next_cost + puzzle.heuristic(&next),
+        next_cost,
+        next,
+      )));
+    }
+  }
+
+  Err(SearchError::Unsolvable)
+}
@@ -0,0 +1,38 @@
+use alloc::collections::{BTreeMap, VecDeque};
+
+use crate::{
+  error::SearchError,
+  puzzle::Puzzle,
+  solution::{self, Solution},
+};
+
+/// breadth first search for the fewest actions, ignores [Puzzle::cost],
+/// gives up after reaching more than limit states
+pub fn bfs<P: Puzzle>(puzzle: &P, limit: usize) -> Result<Solution<P::Action>, SearchError> {
+  let initial = puzzle.initial();
+
+  let mut parents = BTreeMap::new();
+  parents.insert(initial.clone(), None);
+  let mut queue = VecDeque::from([initial]);
+
+  while let Some(state) = queue.pop_front() {
+    if puzzle.is_goal(&state) {
+      return Ok(solution::from_parents(puzzle, &parents, state));
+    }
+
+    for action in puzzle.actions(&state) {
+      let next = puzzle.apply(&state, &action);
+      if parents.contains_key(&next) {
+        continue;
+      }
+      if parents.len() >= limit {
+        return Err(SearchError::LimitExceeded { limit });
+      }
+
+      parents.insert(next.clone(), Some((state.clone(), action)));
+      queue.push_back(next);
+    }
+  }
+
+  Err(SearchError::Unsolvable)
+}
@@ -0,0 +1,65 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use crate::{error::SearchError, puzzle::Puzzle, solution::Solution};
+
+/// depth first search for the cheapest actions by [Puzzle::cost],
+/// remembers the cheapest cost each state is reached with to skip reaching it again no cheaper,
+/// and skips paths that can't be cheaper than the best solution so far by [Puzzle::heuristic],
+/// gives up after reaching more than limit states,
+/// recurses once for every action on the path, so a path as long as limit can overflow the stack,
+/// keep limit small or use [astar](crate::astar::astar) for puzzles with long solutions
+pub fn dfs<P: Puzzle>(puzzle: &P, limit: usize) -> Result<Solution<P::Action>, SearchError> {
+  let mut search = Search {
+    puzzle,
+    limit,
+    costs: BTreeMap::new(),
+    actions: vec![],
+    best: None,
+  };
+
+  search.visit(puzzle.initial(), 0)?;
+
+  search.best.ok_or(SearchError::Unsolvable)
+}
+
+struct Search<'a, P: Puzzle> {
+  puzzle: &'a P,
+  limit: usize,
+  costs: BTreeMap<P::State, u64>,
+  actions: Vec<P::Action>,
+  best: Option<Solution<P::Action>>,
+}
+
+impl<P: Puzzle> Search<'_, P> {
+  fn visit(&mut self, state: P::State, cost: u64) -> Result<(), SearchError> {
+    let bound = cost + self.puzzle.heuristic(&state);
+    if self.best.as_ref().is_some_and(|it| it.cost <= bound) {
+      return Ok(());
+    }
+
+    match self.costs.get(&state) {
+      Some(it) if *it <= cost => return Ok(()),
+      None if self.costs.len() >= self.limit => {
+        return Err(SearchError::LimitExceeded { limit: self.limit })
+      }
+      _ => {}
+    }
+    self.costs.insert(state.clone(), cost);
+
+    if self.puzzle.is_goal(&state) {
+      self.best = Some(Solution::new(self.actions.clone(), cost));
+      return Ok(());
+    }
+
+    for action in self.puzzle.actions(&state) {
+      let next = self.puzzle.apply(&state, &action);
+      let next_cost = cost + self.puzzle.cost(&state, &action);
+
+      self.actions.push(action);
+      self.visit(next, next_cost)?;
+      self.actions.pop();
+    }
+
+    Ok(())
+  }
+}
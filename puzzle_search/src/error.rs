@@ -0,0 +1,26 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError {
+  /// every reachable state is searched without reaching a goal
+  Unsolvable,
+  /// more states than the limit would have to be searched
+  LimitExceeded { limit: usize },
+  /// every solution, if any, takes more actions than the depth
+  DepthExceeded { depth: usize },
+}
+
+impl core::fmt::Display for SearchError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      SearchError::Unsolvable => write!(f, "no solution"),
+      SearchError::LimitExceeded { limit } => {
+        write!(f, "search limit exceeded, more than {limit} states")
+      }
+      SearchError::DepthExceeded { depth } => {
+        write!(f, "no solution within {depth} actions")
+      }
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SearchError {}
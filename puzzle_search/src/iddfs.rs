@@ -0,0 +1,83 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{error::SearchError, puzzle::Puzzle, solution::Solution};
+
+/// iterative deepening depth first search for the fewest actions, ignores [Puzzle::cost],
+/// only keeps the current path in memory, gives up after max_depth actions,
+/// proving a puzzle unsolvable walks every path without cycle so keep max_depth small
+pub fn iddfs<P: Puzzle>(puzzle: &P, max_depth: usize) -> Result<Solution<P::Action>, SearchError> {
+  for depth in 0..=max_depth {
+    let mut path = vec![puzzle.initial()];
+    let mut actions = vec![];
+
+    match search(puzzle, &mut path, &mut actions, depth) {
+      Outcome::Found => {
+        let cost = path
+          .iter()
+          .zip(&actions)
+          .map(|(state, action)| puzzle.cost(state, action))
+          .sum();
+
+        return Ok(Solution::new(actions, cost));
+      }
+      Outcome::CutOff => {}
+      Outcome::Exhausted => return Err(SearchError::Unsolvable),
+    }
+  }
+
+  Err(SearchError::DepthExceeded { depth: max_depth })
+}
+
+enum Outcome {
+  Found,
+  /// some path is longer than the depth
+  CutOff,
+  /// every path without cycle is searched
+  Exhausted,
+}
+
+fn search<P: Puzzle>(
+  puzzle: &P,
+  path: &mut Vec<P::State>,
+  actions: &mut Vec<P::Action>,
+  depth: usize,
+) -> Outcome {
+  let state = path
+    .last()
+    .expect("path starts with the initial state")
+    .clone();
+
+  if puzzle.is_goal(&state) {
+    return Outcome::Found;
+  }
+  if depth == 0 {
+    return Outcome::CutOff;
+  }
+
+  let mut cut_off = false;
+
+  for action in puzzle.actions(&state) {
+    let next = puzzle.apply(&state, &action);
+    if path.contains(&next) {
+      continue;
+    }
+
+    path.push(next);
+    actions.push(action);
+
+    match search(puzzle, path, actions, depth - 1) {
+      Outcome::Found => return Outcome::Found,
+      Outcome::CutOff => cut_off = true,
+      Outcome::Exhausted => {}
+    }
+
+    path.pop();
+    actions.pop();
+  }
+
+  if cut_off {
+    Outcome::CutOff
+  } else {
+    Outcome::Exhausted
+  }
+}
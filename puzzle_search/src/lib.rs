@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod astar;
+pub mod bfs;
+pub mod dfs;
+pub mod error;
+pub mod iddfs;
+pub mod puzzle;
+pub mod solution;
@@ -0,0 +1,27 @@
+use alloc::vec::Vec;
+
+/// a single player puzzle, searched from [Puzzle::initial] to a state where [Puzzle::is_goal]
+pub trait Puzzle {
+  type State: Clone + Ord;
+  type Action: Clone;
+
+  fn initial(&self) -> Self::State;
+
+  /// actions available in the state
+  fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+  fn apply(&self, state: &Self::State, action: &Self::Action) -> Self::State;
+
+  fn is_goal(&self, state: &Self::State) -> bool;
+
+  /// cost of applying the action in the state, 1 by default
+  fn cost(&self, _state: &Self::State, _action: &Self::Action) -> u64 {
+    1
+  }
+
+  /// lower bound of the cost from the state to a goal, used by A*,
+  /// must never overestimate for the solution to be the cheapest
+  fn heuristic(&self, _state: &Self::State) -> u64 {
+    0
+  }
+}
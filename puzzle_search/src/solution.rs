@@ -0,0 +1,43 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use crate::puzzle::Puzzle;
+
+/// the state and action each reached state came from, None for the initial state
+pub(crate) type Parents<P> =
+  BTreeMap<<P as Puzzle>::State, Option<(<P as Puzzle>::State, <P as Puzzle>::Action)>>;
+
+/// actions from the initial state to a goal, and the total cost of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<A> {
+  pub actions: Vec<A>,
+  pub cost: u64,
+}
+
+impl<A> Solution<A> {
+  pub fn new(actions: Vec<A>, cost: u64) -> Self {
+    Self { actions, cost }
+  }
+}
+
+/// walk the parents back from the goal
+pub(crate) fn from_parents<P: Puzzle>(
+  puzzle: &P,
+  parents: &Parents<P>,
+  goal: P::State,
+) -> Solution<P::Action> {
+  let mut steps = vec![];
+  let mut state = goal;
+
+  while let Some(Some((parent, action))) = parents.get(&state) {
+    steps.push((parent.clone(), action.clone()));
+    state = parent.clone();
+  }
+  steps.reverse();
+
+  let cost = steps
+    .iter()
+    .map(|(state, action)| puzzle.cost(state, action))
+    .sum();
+
+  Solution::new(steps.into_iter().map(|(_, action)| action).collect(), cost)
+}
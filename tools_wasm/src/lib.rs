@@ -1,42 +1,169 @@
+use std::str::FromStr;
+
 use wasm_bindgen::prelude::*;
 
 use navigation_compass_solver::action::Action;
+use navigation_compass_solver::error::SolveError;
 use navigation_compass_solver::linkage::Linkage;
 use navigation_compass_solver::navigation_compass::NavigationCompass;
+use navigation_compass_solver::puzzle::CompassPuzzle;
 use navigation_compass_solver::ring::Ring;
+use navigation_compass_solver::table;
 
+/// rings are described by the values at the same index, from inner to outer,
+/// targets default to zero, linkages are digits for each ring, eg. 101,
+/// result is linkages for every step, or error message
 #[wasm_bindgen]
-#[allow(clippy::too_many_arguments)]
 pub fn navigation_compass_solve(
-  ic: i8,
-  in_n: u8,
-  id: i8,
+  currents: Vec<i8>,
+  nums: Vec<u8>,
+  directions: Vec<i8>,
+  positions: Vec<u8>,
+  targets: Option<Vec<i8>>,
+
+  linkages: Vec<String>,
+) -> Result<Vec<String>, JsError> {
+  let puzzle = build_puzzle(currents, nums, directions, positions, targets, linkages)?;
+
+  solve_puzzle(puzzle)
+}
+
+/// same as [navigation_compass_solve], with the puzzle in one line,
+/// eg. 4:1:-1,0:3:-1,0:3:1/011,101,110
+#[wasm_bindgen]
+pub fn navigation_compass_solve_puzzle(puzzle: &str) -> Result<Vec<String>, JsError> {
+  solve_puzzle(CompassPuzzle::from_str(puzzle)?)
+}
 
-  mc: i8,
-  mn: u8,
-  md: i8,
+/// the next step toward the target, from the current values of the rings
+#[wasm_bindgen(getter_with_clone)]
+pub struct NavigationCompassHint {
+  /// linkage to rotate, None if already solved
+  pub linkage: Option<String>,
+  /// steps left to reach the target, including this one
+  pub remaining: usize,
+}
+
+/// only the next step of [navigation_compass_solve], same arguments,
+/// to guide one step at a time
+#[wasm_bindgen]
+pub fn navigation_compass_hint(
+  currents: Vec<i8>,
+  nums: Vec<u8>,
+  directions: Vec<i8>,
+  positions: Vec<u8>,
+  targets: Option<Vec<i8>>,
+
+  linkages: Vec<String>,
+) -> Result<NavigationCompassHint, JsError> {
+  let puzzle = build_puzzle(currents, nums, directions, positions, targets, linkages)?;
+  let result = solve_puzzle(puzzle)?;
+
+  Ok(NavigationCompassHint {
+    remaining: result.len(),
+    linkage: result.into_iter().next(),
+  })
+}
+
+/// puzzle in one line, same arguments as [navigation_compass_solve]
+#[wasm_bindgen]
+pub fn navigation_compass_format(
+  currents: Vec<i8>,
+  nums: Vec<u8>,
+  directions: Vec<i8>,
+  positions: Vec<u8>,
+  targets: Option<Vec<i8>>,
+
+  linkages: Vec<String>,
+) -> Result<String, JsError> {
+  let puzzle = build_puzzle(currents, nums, directions, positions, targets, linkages)?;
+
+  Ok(puzzle.to_string())
+}
 
-  oc: i8,
-  on: u8,
-  od: i8,
+/// values of a puzzle in one line, in the same layout as the arguments of [navigation_compass_solve]
+#[wasm_bindgen(getter_with_clone)]
+pub struct NavigationCompassInput {
+  pub currents: Vec<i8>,
+  pub nums: Vec<u8>,
+  pub directions: Vec<i8>,
+  pub positions: Vec<u8>,
+  pub targets: Vec<i8>,
+
+  pub linkages: Vec<String>,
+}
+
+#[wasm_bindgen]
+pub fn navigation_compass_parse(puzzle: &str) -> Result<NavigationCompassInput, JsError> {
+  let puzzle = CompassPuzzle::from_str(puzzle)?;
+  let rings = puzzle.compass.rings();
+
+  Ok(NavigationCompassInput {
+    currents: rings.iter().map(|it| it.current).collect(),
+    nums: rings.iter().map(|it| it.num).collect(),
+    directions: rings.iter().map(|it| it.direction).collect(),
+    positions: rings.iter().map(|it| it.positions).collect(),
+    targets: rings.iter().map(|it| it.target).collect(),
+
+    linkages: puzzle.linkages.iter().map(Linkage::to_string).collect(),
+  })
+}
+
+fn build_puzzle(
+  currents: Vec<i8>,
+  nums: Vec<u8>,
+  directions: Vec<i8>,
+  positions: Vec<u8>,
+  targets: Option<Vec<i8>>,
+
+  linkages: Vec<String>,
+) -> Result<CompassPuzzle, JsError> {
+  if currents.len() != nums.len()
+    || currents.len() != directions.len()
+    || currents.len() != positions.len()
+  {
+    return Err(JsError::new("ring values have different lengths"));
+  }
+
+  let targets = targets.unwrap_or_else(|| vec![0; currents.len()]);
+  if currents.len() != targets.len() {
+    return Err(JsError::from(SolveError::InvalidTarget(format!(
+      "{targets:?}"
+    ))));
+  }
 
-  linkages: Vec<u8>,
-) -> Option<Vec<u8>> {
   let navigation_compass = NavigationCompass::new(
-    Ring::new(ic, in_n, id),
-    Ring::new(mc, mn, md),
-    Ring::new(oc, on, od),
+    currents
+      .into_iter()
+      .zip(nums)
+      .zip(directions)
+      .zip(positions)
+      .zip(targets)
+      .map(|((((current, num), direction), positions), target)| {
+        Ring::new(current, num, direction, positions).with_target(target)
+      })
+      .collect(),
   );
 
-  let result = navigation_compass.try_solve(linkages.into_iter().map(Linkage::new).collect());
+  let linkages = linkages
+    .iter()
+    .map(|it| Linkage::parse(it))
+    .collect::<Result<Vec<_>, _>>()?;
 
-  result.map(|it| {
-    it.into_iter()
+  Ok(CompassPuzzle::new(navigation_compass, linkages))
+}
+
+fn solve_puzzle(puzzle: CompassPuzzle) -> Result<Vec<String>, JsError> {
+  let result = table::solve(&puzzle.compass, puzzle.linkages)?;
+
+  Ok(
+    result
+      .into_iter()
       .map(|it| match it {
-        Action::Rotate(linkage) => linkage.to_u8(),
+        Action::Rotate(linkage) => linkage.to_string(),
       })
-      .collect::<Vec<_>>()
-  })
+      .collect::<Vec<_>>(),
+  )
 }
 
 #[wasm_bindgen]
@@ -1,7 +1,11 @@
 import { NavigationCompass } from "./navigationCompassSolver/NavigationCompass";
 import { createSignal, For, Setter, Show, Signal } from "solid-js";
 import { Panel } from "../../component/Panel";
-import { navigation_compass_solve } from "tools_wasm";
+import {
+  navigation_compass_format,
+  navigation_compass_parse,
+  navigation_compass_solve,
+} from "tools_wasm";
 
 type Amount = 1 | 2 | 3 | 4;
 type Direction = -1 | 1;
@@ -24,25 +28,71 @@ export function NavigationCompassSolver() {
   ];
 
   const [result, setResult] = createSignal<null | string | number[]>(null);
+  const [puzzle, setPuzzle] = createSignal("");
+
+  function puzzleArgs() {
+    return [
+      new Int8Array([inner(), middle(), outer()]),
+      new Uint8Array([innerN(), middleN(), outerN()]),
+      new Int8Array([innerD(), middleD(), outerD()]),
+      new Uint8Array([6, 6, 6]),
+      undefined,
+      // a linkage without any ring is an unused slot
+      linkages
+        .map((it) => it[0]())
+        .filter((it) => it !== 0)
+        .map((it) => it.toString(2).padStart(3, "0")),
+    ] as const;
+  }
 
   function solve() {
-    let navigationCompassResult = navigation_compass_solve(
-      inner(),
-      innerN(),
-      innerD(),
-      middle(),
-      middleN(),
-      middleD(),
-      outer(),
-      outerN(),
-      outerD(),
-      new Uint8Array(linkages.map((it) => it[0]()))
-    );
+    try {
+      let navigationCompassResult = navigation_compass_solve(...puzzleArgs());
+
+      setResult(navigationCompassResult.map((it) => Number.parseInt(it, 2)));
+    } catch (e) {
+      setResult(e instanceof Error ? e.message : "无解");
+    }
+  }
 
-    if (navigationCompassResult != null) {
-      setResult(Array.from(navigationCompassResult));
-    } else {
-      setResult("无法在 100 步内得出结果");
+  function exportPuzzle() {
+    try {
+      setPuzzle(navigation_compass_format(...puzzleArgs()));
+    } catch (e) {
+      setResult(e instanceof Error ? e.message : "导出失败");
+    }
+  }
+
+  function importPuzzle() {
+    try {
+      const input = navigation_compass_parse(puzzle());
+      if (
+        input.currents.length !== 3 ||
+        input.linkages.length > linkages.length ||
+        input.linkages.some((it) => !/^[01]{3}$/.test(it)) ||
+        input.positions.some((it) => it !== 6) ||
+        input.targets.some((it) => it !== 0)
+      ) {
+        setResult("仅支持三圈六格, 目标为零且最多三个普通联动的谜题");
+        return;
+      }
+
+      [setInner, setMiddle, setOuter].forEach((set, idx) =>
+        set(input.currents[idx])
+      );
+      [setInnerN, setMiddleN, setOuterN].forEach((set, idx) =>
+        set(input.nums[idx] as Amount)
+      );
+      [setInnerD, setMiddleD, setOuterD].forEach((set, idx) =>
+        set(input.directions[idx] as Direction)
+      );
+      // slots left over are cleared, and not used when solving
+      linkages.forEach(([val, set], idx) =>
+        set(Number.parseInt(input.linkages[idx] ?? "000", 2))
+      );
+      setResult(null);
+    } catch (e) {
+      setResult(e instanceof Error ? e.message : "导入失败");
     }
   }
 
@@ -120,6 +170,20 @@ export function NavigationCompassSolver() {
           重置
         </button>
       </Panel>
+      <Panel class={"flex gap-4"}>
+        <input
+          class={"w-full font-mono"}
+          placeholder={"4:1:-1,0:3:-1,0:3:1/011,101,110"}
+          value={puzzle()}
+          onInput={(e) => setPuzzle(e.target.value)}
+        />
+        <button class={"break-keep"} onclick={() => exportPuzzle()}>
+          导出
+        </button>
+        <button class={"break-keep"} onclick={() => importPuzzle()}>
+          导入
+        </button>
+      </Panel>
     </div>
   );
 }